@@ -0,0 +1,91 @@
+//! Accessibility settings.
+use amethyst::{
+    core::timing::Time,
+    ecs::{World, WorldExt},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{pong::Side, theme::Theme};
+
+/// Loaded from `config/accessibility.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    /// Keep the camera still, no matter what the effects settings say.
+    pub reduce_motion: bool,
+    /// Opaque white text on a black background, instead of the theme's colours.
+    pub high_contrast: bool,
+    /// Colours of the paddles, the ball and the scores.
+    pub palette: Palette,
+    /// Size of the ball relative to the normal one, it's drawn and collides at that size.
+    pub ball_scale: f32,
+    /// Speed of the matches relative to the normal one, e.g. `0.75` to slow them down.
+    pub game_speed: f32,
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        AccessibilityConfig {
+            reduce_motion: false,
+            high_contrast: false,
+            palette: Palette::Theme,
+            ball_scale: 1.0,
+            game_speed: 1.0,
+        }
+    }
+}
+
+impl AccessibilityConfig {
+    /// Replace the colours of the theme that are hard to read with high contrast.
+    pub fn adjust(&self, theme: &mut Theme) {
+        if self.high_contrast {
+            theme.background = [0.0, 0.0, 0.0, 1.0];
+            theme.letterbox = [0.0, 0.0, 0.0, 1.0];
+            theme.score_color = [1.0, 1.0, 1.0, 1.0];
+            theme.text_color = [1.0, 1.0, 1.0, 1.0];
+        }
+    }
+}
+
+/// Run time at the configured `game_speed` during a match, or at the normal speed outside of it.
+pub fn set_game_speed(world: &World, in_match: bool) {
+    let speed = match world.try_fetch::<AccessibilityConfig>() {
+        Some(config) if in_match => config.game_speed,
+        _ => 1.0,
+    };
+    world.write_resource::<Time>().set_time_scale(speed);
+}
+
+/// Colours that tell the two sides apart, picked from the Okabe-Ito palette.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Palette {
+    /// The colours of the theme.
+    Theme,
+    /// Blue and orange, for protanopia and deuteranopia.
+    RedGreen,
+    /// Vermillion and bluish green, for tritanopia.
+    BlueYellow,
+}
+
+impl Palette {
+    /// Colour of the paddle and the score of a side, `None` keeps the theme's.
+    pub fn side(self, side: Side) -> Option<[f32; 4]> {
+        let [left, right] = match self {
+            Palette::Theme => return None,
+            Palette::RedGreen => [[0.0, 0.447, 0.698, 1.0], [0.902, 0.624, 0.0, 1.0]],
+            Palette::BlueYellow => [[0.835, 0.369, 0.0, 1.0], [0.0, 0.62, 0.451, 1.0]],
+        };
+        Some(match side {
+            Side::Left => left,
+            Side::Right => right,
+        })
+    }
+
+    /// Colour of the ball, that stands out against both sides, `None` keeps the theme's.
+    pub fn ball(self) -> Option<[f32; 4]> {
+        match self {
+            Palette::Theme => None,
+            Palette::RedGreen => Some([0.941, 0.894, 0.259, 1.0]),
+            Palette::BlueYellow => Some([1.0, 1.0, 1.0, 1.0]),
+        }
+    }
+}
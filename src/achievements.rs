@@ -0,0 +1,160 @@
+//! Achievements for feats in a match, defined in `config/achievements.ron`
+//! and unlocked once and for all on this computer.
+use amethyst::{
+    assets::Loader,
+    ecs::{prelude::Entity, world::Builder, World, WorldExt},
+    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    utils::removal::Removal,
+};
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::Path};
+
+use crate::{
+    audio::ScoreSound,
+    paths::{load_ron, save_ron},
+    pong::ScaledUi,
+    states::State,
+    theme::Theme,
+};
+
+/// All achievements, loaded from `config/achievements.ron`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Achievements {
+    pub achievements: Vec<Achievement>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Achievement {
+    /// Stays the same, when the name changes. Unlocked achievements are saved by it.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub goal: Goal,
+}
+
+/// What a player has to do to unlock an achievement.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Goal {
+    /// Hit the ball back and forth this many times without a point being scored.
+    Rally { hits: u32 },
+    /// Score while the ball spins at least this fast, either way.
+    ScoreWithSpin { spin: f32 },
+    /// Score after the ball bounced off the walls this many times since the last paddle hit.
+    BankShot { bounces: u32 },
+    /// Win a match without conceding a point.
+    WinWithoutConceding,
+    /// Hear a point celebrated with the given sound.
+    HearSound(ScoreSound),
+}
+
+/// Something that happened in a match, that may unlock an achievement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Feat {
+    /// A paddle hit the ball, the `hits`th of the rally.
+    PaddleHit {
+        hits: u32,
+    },
+    /// A player scored.
+    Score {
+        spin: f32,
+        wall_bounces: u32,
+    },
+    /// A player won the match.
+    Win {
+        conceded: i32,
+    },
+    Heard(ScoreSound),
+}
+
+impl Goal {
+    pub fn is_met(&self, feat: Feat) -> bool {
+        match (self, feat) {
+            (Goal::Rally { hits }, Feat::PaddleHit { hits: rally }) => rally >= *hits,
+            (Goal::ScoreWithSpin { spin }, Feat::Score { spin: scored, .. }) => {
+                scored.abs() >= *spin
+            }
+            (Goal::BankShot { bounces }, Feat::Score { wall_bounces, .. }) => {
+                wall_bounces >= *bounces
+            }
+            (Goal::WinWithoutConceding, Feat::Win { conceded }) => conceded == 0,
+            (Goal::HearSound(sound), Feat::Heard(heard)) => *sound == heard,
+            _ => false,
+        }
+    }
+}
+
+/// The achievements unlocked so far.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    /// Ids of the unlocked achievements, in the order they were unlocked.
+    pub unlocked: Vec<String>,
+}
+
+impl AchievementProgress {
+    /// Load the progress, starting from scratch if there is none.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        load_ron(path).unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_ron(self, path)
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == id)
+    }
+
+    /// Unlock the achievements the feat is enough for, returns the newly unlocked ones.
+    pub fn unlock<'a>(
+        &mut self,
+        achievements: &'a Achievements,
+        feat: Feat,
+    ) -> Vec<&'a Achievement> {
+        let unlocked = achievements
+            .achievements
+            .iter()
+            .filter(|achievement| !self.is_unlocked(&achievement.id))
+            .filter(|achievement| achievement.goal.is_met(feat))
+            .collect::<Vec<_>>();
+        self.unlocked
+            .extend(unlocked.iter().map(|achievement| achievement.id.clone()));
+        unlocked
+    }
+}
+
+/// The ui text announcing unlocked achievements.
+pub struct AchievementToast {
+    pub text: Entity,
+}
+
+/// Create the text of the achievement toasts, that is removed with `State::Game`.
+pub fn initialize_toast(world: &mut World) {
+    world.register::<ScaledUi>();
+    let (font, color) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (theme.score_font.clone(), theme.text_color)
+    };
+    let font = world
+        .read_resource::<Loader>()
+        .load(font, TtfFormat, (), &world.read_resource());
+    // Above the spin meter
+    let transform = UiTransform::new(
+        "AchievementToast".to_string(),
+        Anchor::BottomMiddle,
+        Anchor::BottomMiddle,
+        0.,
+        60.,
+        1.,
+        480.,
+        30.,
+    );
+    let text = world
+        .create_entity()
+        .with(ScaledUi::new(&transform, 16.))
+        .with(transform)
+        .with(UiText::new(font, String::new(), color, 16.))
+        .with(Removal::new(State::Game))
+        .build();
+    world.insert(AchievementToast { text });
+}
@@ -0,0 +1,20 @@
+//! The attract mode: an AI match behind the main menu, once nobody has touched anything for a while.
+use serde::{Deserialize, Serialize};
+
+/// Loaded from `config/attract.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttractConfig {
+    /// Seconds without any input in the main menu before the demo starts, `None` never starts it.
+    pub delay: Option<f32>,
+    /// Background of the main menu while the demo plays, as (red, green, blue, alpha).
+    pub menu_background: [f32; 4],
+}
+
+impl Default for AttractConfig {
+    fn default() -> Self {
+        AttractConfig {
+            delay: Some(20.0),
+            menu_background: [0.0, 0.0, 0.0, 0.2],
+        }
+    }
+}
@@ -4,6 +4,7 @@ use amethyst::{
     ecs::{World, WorldExt},
 };
 use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 
 use std::{iter::Cycle, vec::IntoIter};
 
@@ -38,9 +39,15 @@ fn load_audio_track(loader: &Loader, world: &World, file: &str) -> SourceHandle
 /// Initialise audio in the world. This will eventually include
 /// the background tracks as well as the sound effects, but for now
 /// we'll just work on sound effects.
+/// Without an audio device the game runs silently.
 pub fn initialize_audio(world: &mut World) {
+    let sink = world
+        .try_fetch::<Output>()
+        .map(|output| AudioSink::new(&output));
+    if let Some(sink) = sink {
+        world.insert(sink);
+    }
     let (sound_effects, music) = {
-        world.insert(AudioSink::new(&Default::default()));
         let loader = world.read_resource::<Loader>();
 
         let sound = Sounds {
@@ -51,8 +58,9 @@ pub fn initialize_audio(world: &mut World) {
             roblox_death_sfx: load_audio_track(&loader, &world, ROBLOX_SOUND),
         };
 
-        let mut sink = world.write_resource::<AudioSink>();
-        sink.set_volume(0.05); // Music is a bit loud, reduce the volume.
+        if let Some(mut sink) = world.try_fetch_mut::<AudioSink>() {
+            sink.set_volume(0.05); // Music is a bit loud, reduce the volume.
+        }
 
         let music = MUSIC_TRACKS
             .iter()
@@ -95,21 +103,32 @@ pub fn play_bounce_paddle_sound(
     }
 }
 
-pub fn play_score_sound(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
-    if let Some(ref output) = output.as_ref() {
-        let lucky_nr = thread_rng().gen_range(0.0, 1.0);
-        if lucky_nr > 0.99 {
-            if let Some(sound) = storage.get(&sounds.wilhelm_sfx) {
-                output.play_once(sound, 0.3);
-            }
-        } else if lucky_nr > 0.95 {
-            if let Some(sound) = storage.get(&sounds.roblox_death_sfx) {
-                output.play_once(sound, 0.5);
-            }
-        } else {
-            if let Some(sound) = storage.get(&sounds.score_sfx) {
-                output.play_once(sound, 0.3);
-            }
-        }
-    }
+/// The sound a score is celebrated with, sent by `WinnerSystem`
+/// through an `EventChannel<ScoreSound>` when it was heard.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScoreSound {
+    Score,
+    RobloxDeath,
+    /// The 1% chance.
+    Wilhelm,
+}
+
+/// Play one of the score sounds, returns which one was played.
+pub fn play_score_sound(
+    sounds: &Sounds,
+    storage: &AssetStorage<Source>,
+    output: Option<&Output>,
+) -> Option<ScoreSound> {
+    let output = output?;
+    let lucky_nr = thread_rng().gen_range(0.0, 1.0);
+    let (sound, handle, volume) = if lucky_nr > 0.99 {
+        (ScoreSound::Wilhelm, &sounds.wilhelm_sfx, 0.3)
+    } else if lucky_nr > 0.95 {
+        (ScoreSound::RobloxDeath, &sounds.roblox_death_sfx, 0.5)
+    } else {
+        (ScoreSound::Score, &sounds.score_sfx, 0.3)
+    };
+    let source = storage.get(handle)?;
+    output.play_once(source, volume);
+    Some(sound)
 }
@@ -0,0 +1,185 @@
+#![warn(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+//! Plays matches between AI controllers as fast as possible,
+//! without window or audio, and reports how they did.
+//! This is used to tune the difficulty levels of the `AiSystem`.
+
+use amethyst::ecs::{Join, WorldExt};
+use amethyst_pong::{
+    headless::Simulation,
+    pong::{Ball, Difficulty, ScoreBoard, Side},
+    rules::{MatchPhase, MatchRules, TieBreak},
+};
+use structopt::StructOpt;
+
+/// Matches that are still tied after this many seconds of sudden death are given up.
+const MAX_SUDDEN_DEATH: f32 = 600.0;
+/// Width of the buckets of the ball speed distribution.
+const SPEED_BUCKET: f32 = 25.0;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Plays matches between AI opponents without a window and reports the results")]
+struct Opt {
+    /// Number of matches to play.
+    #[structopt(long, default_value = "100")]
+    matches: u64,
+    /// Seed of the first match, every following match counts up from it.
+    #[structopt(long, default_value = "0")]
+    seed: u64,
+    /// Difficulty of the left AI: `easy`, `normal` or `hard`.
+    #[structopt(long, default_value = "normal")]
+    left: Difficulty,
+    /// Difficulty of the right AI: `easy`, `normal` or `hard`.
+    #[structopt(long, default_value = "normal")]
+    right: Difficulty,
+    /// Length of a match in seconds. Ties are decided by sudden death.
+    #[structopt(long, default_value = "180")]
+    duration: f32,
+    /// Simulated seconds per frame.
+    #[structopt(long, default_value = "0.016666668")]
+    timestep: f32,
+}
+
+/// Statistics over all played matches.
+#[derive(Default)]
+struct Report {
+    matches: u64,
+    left_wins: u64,
+    right_wins: u64,
+    /// Matches given up after `MAX_SUDDEN_DEATH`.
+    unfinished: u64,
+    /// Number of finished rallies.
+    rallies: u64,
+    /// Number of paddle hits in finished rallies.
+    returns: u64,
+    points: u64,
+    seconds: f64,
+    /// Number of frames the ball spent in each speed bucket.
+    speeds: Vec<u64>,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    let rules = MatchRules {
+        duration: Some(opt.duration),
+        tie_break: TieBreak::SuddenDeath,
+    };
+
+    let mut report = Report::default();
+    for i in 0..opt.matches {
+        play(&opt, &rules, opt.seed.wrapping_add(i), &mut report);
+    }
+    report.print(&opt);
+}
+
+/// Play a single match and add it to the `report`.
+fn play(opt: &Opt, rules: &MatchRules, seed: u64, report: &mut Report) {
+    let mut sim = Simulation::new(
+        rules,
+        Some(opt.left.ai_params()),
+        Some(opt.right.ai_params()),
+        seed,
+    );
+    let limit = opt.duration + MAX_SUDDEN_DEATH;
+    let mut seconds = 0.0;
+    let mut points = 0;
+    let mut returns = 0;
+    let mut direction = ball_state(&sim).0;
+
+    while !sim.is_finished() && seconds < limit {
+        sim.step(opt.timestep);
+        seconds += opt.timestep;
+
+        let scores = sim.world.read_resource::<ScoreBoard>();
+        let total = (scores.score_left + scores.score_right) as u64;
+        let (velocity_x, speed, in_play) = ball_state(&sim);
+        if total != points {
+            // The rally is over, the serve may point anywhere.
+            report.rallies += total - points;
+            report.returns += returns;
+            points = total;
+            returns = 0;
+        } else if in_play && velocity_x.signum() != direction.signum() {
+            returns += 1;
+        }
+        direction = velocity_x;
+
+        if in_play {
+            let bucket = (speed / SPEED_BUCKET) as usize;
+            if report.speeds.len() <= bucket {
+                report.speeds.resize(bucket + 1, 0);
+            }
+            report.speeds[bucket] += 1;
+        }
+    }
+
+    report.matches += 1;
+    report.points += points;
+    report.seconds += f64::from(seconds);
+    match sim.phase() {
+        MatchPhase::Finished(Side::Left) => report.left_wins += 1,
+        MatchPhase::Finished(Side::Right) => report.right_wins += 1,
+        _ => report.unfinished += 1,
+    }
+}
+
+/// The horizontal velocity and the speed of the ball, and whether it's in play.
+fn ball_state(sim: &Simulation<'_, '_>) -> (f32, f32, bool) {
+    let balls = sim.world.read_storage::<Ball>();
+    match (&balls).join().next() {
+        Some(ball) => (ball.velocity.x, ball.velocity.norm(), ball.hidden.is_none()),
+        None => (0.0, 0.0, false),
+    }
+}
+
+impl Report {
+    fn print(&self, opt: &Opt) {
+        let matches = self.matches.max(1) as f64;
+        println!(
+            "{} matches, {:?} (left) vs {:?} (right), seeds {}..{}",
+            self.matches,
+            opt.left,
+            opt.right,
+            opt.seed,
+            opt.seed.wrapping_add(self.matches)
+        );
+        println!();
+        println!(
+            "Left wins   {:>6}  {:5.1}%",
+            self.left_wins,
+            100.0 * self.left_wins as f64 / matches
+        );
+        println!(
+            "Right wins  {:>6}  {:5.1}%",
+            self.right_wins,
+            100.0 * self.right_wins as f64 / matches
+        );
+        println!(
+            "Unfinished  {:>6}  {:5.1}%",
+            self.unfinished,
+            100.0 * self.unfinished as f64 / matches
+        );
+        println!();
+        println!(
+            "Average rally length   {:6.2} returns",
+            self.returns as f64 / self.rallies.max(1) as f64
+        );
+        println!(
+            "Points per minute      {:6.2}",
+            self.points as f64 / (self.seconds / 60.0).max(f64::EPSILON)
+        );
+        println!();
+        println!("Ball speeds");
+        let frames: u64 = self.speeds.iter().sum();
+        for (bucket, count) in self.speeds.iter().enumerate() {
+            let share = 100.0 * *count as f64 / frames.max(1) as f64;
+            println!(
+                "  {:>4.0} - {:>4.0}  {:5.1}%  {}",
+                bucket as f32 * SPEED_BUCKET,
+                (bucket + 1) as f32 * SPEED_BUCKET,
+                share,
+                "#".repeat(share.round() as usize)
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! The single player campaign: a sequence of AI opponents,
+//! each one unlocked by beating the previous one.
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::Path};
+
+use crate::{
+    paths::{load_ron, save_ron},
+    pong::AiParams,
+    rules::MatchRules,
+};
+
+/// All stages of the campaign, loaded from `config/campaign.ron`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Campaign {
+    pub stages: Vec<Stage>,
+}
+
+/// A single opponent of the campaign.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Stage {
+    pub name: String,
+    pub ai: AiParams,
+    pub arena: Arena,
+    pub rules: MatchRules,
+}
+
+/// Modifiers of the arena a stage is played in.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Arena {
+    /// Multiplier for the speed of every serve.
+    pub ball_speed_factor: f32,
+}
+
+/// The player's progress through the campaign.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    /// Number of stages that can be played.
+    /// The first stage is always unlocked.
+    pub unlocked: usize,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena {
+            ball_speed_factor: 1.0,
+        }
+    }
+}
+
+impl CampaignProgress {
+    /// Load the progress, starting from scratch if there is none.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        load_ron(path).unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_ron(self, path)
+    }
+
+    pub fn is_unlocked(&self, stage: usize) -> bool {
+        stage == 0 || stage < self.unlocked
+    }
+
+    /// Record a win against the given stage, unlocking the next one.
+    pub fn beat(&mut self, stage: usize) {
+        self.unlocked = self.unlocked.max(stage + 2);
+    }
+}
@@ -0,0 +1,84 @@
+//! Command line options.
+use structopt::StructOpt;
+
+use std::{path::PathBuf, str::FromStr};
+
+use crate::pong::Difficulty;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "A Pong clone written in Rust using the Amethyst game engine")]
+pub struct Opt {
+    /// Skip the main menu and start a match right away.
+    /// Either `single-player` or `two-players`.
+    #[structopt(long)]
+    pub mode: Option<StartMode>,
+    /// Strength of the AI opponent: `easy`, `normal` or `hard`.
+    #[structopt(long, default_value = "normal")]
+    pub difficulty: Difficulty,
+    /// Seed for serves and launches, making matches reproducible.
+    #[structopt(long)]
+    pub seed: Option<u64>,
+    /// Record the match started with `--mode` and save the replay to this file, when it's over.
+    #[structopt(long, parse(from_os_str), requires = "mode")]
+    pub record: Option<PathBuf>,
+    /// Play back a replay recorded with `--record`, instead of starting at the main menu.
+    #[structopt(long, parse(from_os_str), conflicts_with_all = &["mode", "record"])]
+    pub replay: Option<PathBuf>,
+    /// Directory containing the shipped configuration files. Defaults to `config` next to the executable.
+    #[structopt(long, parse(from_os_str))]
+    pub config_dir: Option<PathBuf>,
+    /// Config file overriding single settings of the other config files,
+    /// e.g. `(display: (fullscreen: None), rules: (duration: None))`.
+    #[structopt(long, parse(from_os_str))]
+    pub config: Option<PathBuf>,
+    /// Print the effective configuration of all config files and exit.
+    #[structopt(long)]
+    pub dump_config: bool,
+    /// Directory containing the assets. Defaults to `assets` next to the executable.
+    #[structopt(long, parse(from_os_str))]
+    pub assets_dir: Option<PathBuf>,
+    /// Width of the window in pixels.
+    #[structopt(long, requires = "height")]
+    pub width: Option<u32>,
+    /// Height of the window in pixels.
+    #[structopt(long, requires = "width")]
+    pub height: Option<u32>,
+}
+
+/// The mode to start in, instead of the main menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartMode {
+    SinglePlayer,
+    TwoPlayers,
+}
+
+impl FromStr for StartMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single-player" => Ok(StartMode::SinglePlayer),
+            "two-players" => Ok(StartMode::TwoPlayers),
+            _ => Err(format!(
+                "unknown mode `{}`, expected `single-player` or `two-players`",
+                s
+            )),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "unknown difficulty `{}`, expected `easy`, `normal` or `hard`",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Loading of the configuration files.
+//!
+//! Every config file is resolved from layers, each overriding the previous one key by key:
+//!
+//! 1. the built-in defaults,
+//! 2. the shipped `config` directory,
+//! 3. the user's config directory (i.e. `$XDG_CONFIG_HOME/amethyst-pong`),
+//! 4. the file passed with `--config`, that has one field per config file,
+//!    e.g. `(display: (fullscreen: None), rules: (duration: None))`.
+use amethyst::{
+    input::{Bindings, StringBindings},
+    window::DisplayConfig,
+};
+use ron::de::{Deserializer, Error as RonError};
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    accessibility::AccessibilityConfig, achievements::Achievements, attract::AttractConfig,
+    campaign::Campaign, effects::EffectsConfig, error::StartupError, hud::HudConfig,
+    menu::MenuConfig, pause::PauseConfig, practice::PracticeConfig, rules::MatchRules,
+    theme::AppearanceConfig,
+};
+
+mod tree;
+
+use self::tree::{Node, Output, SyntaxError};
+
+/// Name of the built-in defaults in error messages.
+const DEFAULTS: &str = "<built-in defaults>";
+
+/// The effective configuration of all layers.
+/// Serialized, it's a valid `--config` file.
+#[derive(Serialize)]
+pub struct Settings {
+    pub display: DisplayConfig,
+    pub appearance: AppearanceConfig,
+    pub bindings: Bindings<StringBindings>,
+    pub rules: MatchRules,
+    pub practice: PracticeConfig,
+    pub campaign: Campaign,
+    pub effects: EffectsConfig,
+    pub hud: HudConfig,
+    pub accessibility: AccessibilityConfig,
+    pub achievements: Achievements,
+    pub attract: AttractConfig,
+    pub menu: MenuConfig,
+    pub pause: PauseConfig,
+}
+
+impl Settings {
+    /// The config files, by name without the `.ron` extension.
+    pub const FILES: [&'static str; 13] = [
+        "display",
+        "appearance",
+        "bindings",
+        "rules",
+        "practice",
+        "campaign",
+        "effects",
+        "hud",
+        "accessibility",
+        "achievements",
+        "attract",
+        "menu",
+        "pause",
+    ];
+
+    pub fn load(layers: &Layers) -> Result<Self, StartupError> {
+        layers.check_overrides()?;
+        let display = DisplayConfig {
+            title: "Pong!".to_string(),
+            dimensions: Some((500, 500)),
+            ..Default::default()
+        };
+        Ok(Settings {
+            display: layers.load(
+                "display",
+                &display,
+                "a display config with title and dimensions",
+            )?,
+            appearance: layers.load(
+                "appearance",
+                &AppearanceConfig::default(),
+                "appearance settings with the names of the theme and the language",
+            )?,
+            bindings: layers.load_required(
+                "bindings",
+                &Bindings::default(),
+                "input bindings with axes and actions",
+            )?,
+            rules: layers.load(
+                "rules",
+                &MatchRules::default(),
+                "match rules with duration and tie_break",
+            )?,
+            practice: layers.load(
+                "practice",
+                &PracticeConfig::default(),
+                "a practice config with opponent, launcher and drills",
+            )?,
+            campaign: layers.load(
+                "campaign",
+                &Campaign::default(),
+                "a campaign with a list of stages",
+            )?,
+            effects: layers.load(
+                "effects",
+                &EffectsConfig::default(),
+                "effect switches trail, sparks, bursts, screen_shake and hit_stop",
+            )?,
+            hud: layers.load(
+                "hud",
+                &HudConfig::default(),
+                "HUD switches like spin_meter, serve_countdown and score_banner",
+            )?,
+            accessibility: layers.load(
+                "accessibility",
+                &AccessibilityConfig::default(),
+                "accessibility settings like reduce_motion, palette and game_speed",
+            )?,
+            achievements: layers.load(
+                "achievements",
+                &Achievements::default(),
+                "a list of achievements with id, name, description and goal",
+            )?,
+            attract: layers.load(
+                "attract",
+                &AttractConfig::default(),
+                "attract mode settings with delay and menu_background",
+            )?,
+            menu: layers.load("menu", &MenuConfig::default(), "menu settings with confirm")?,
+            pause: layers.load(
+                "pause",
+                &PauseConfig::default(),
+                "pause settings with on_focus_lost, on_gamepad_disconnected and resume_countdown",
+            )?,
+        })
+    }
+
+    /// The settings as pretty printed RON, for bug reports.
+    pub fn dump(&self) -> String {
+        ron::ser::to_string_pretty(self, Default::default()).expect("Settings serialize")
+    }
+}
+
+/// Where the config files are looked up.
+pub struct Layers {
+    /// The `config` directory shipped with the game.
+    pub shipped: PathBuf,
+    /// The user's config directory.
+    pub user: Option<PathBuf>,
+    /// A single file overriding settings of any config file.
+    pub overrides: Option<PathBuf>,
+}
+
+/// A document taking part in the merge.
+struct Document {
+    path: PathBuf,
+    source: String,
+}
+
+impl Layers {
+    /// Load the config file `name`, without the `.ron` extension, from all layers.
+    /// `expected` describes the content of the file for error messages.
+    pub fn load<T>(
+        &self,
+        name: &str,
+        default: &T,
+        expected: &'static str,
+    ) -> Result<T, StartupError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.load_layers(name, default, expected, false)
+    }
+
+    /// Like `load`, but at least one file has to provide the config.
+    pub fn load_required<T>(
+        &self,
+        name: &str,
+        default: &T,
+        expected: &'static str,
+    ) -> Result<T, StartupError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        self.load_layers(name, default, expected, true)
+    }
+
+    fn load_layers<T>(
+        &self,
+        name: &str,
+        default: &T,
+        expected: &'static str,
+        required: bool,
+    ) -> Result<T, StartupError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut docs = vec![Document {
+            path: PathBuf::from(DEFAULTS),
+            source: ron::ser::to_string(default).expect("Defaults serialize"),
+        }];
+        let file_name = format!("{}.ron", name);
+        let shipped = self.shipped.join(&file_name);
+        if shipped.exists() {
+            docs.push(read(&shipped)?);
+        }
+        if let Some(user) = self.user.as_ref().map(|dir| dir.join(&file_name)) {
+            if user.exists() {
+                docs.push(read(&user)?);
+            }
+        }
+
+        let mut root = parse(&docs, 0, expected)?;
+        for doc in 1..docs.len() {
+            root = tree::merge(root, parse(&docs, doc, expected)?);
+        }
+        let mut found = docs.len() > 1;
+        if let Some(path) = &self.overrides {
+            docs.push(read(path)?);
+            if let Some(node) = override_field(parse(&docs, docs.len() - 1, expected)?, name) {
+                root = tree::merge(root, node);
+                found = true;
+            }
+        }
+
+        if !found {
+            if required {
+                return Err(StartupError::Read {
+                    path: shipped,
+                    error: io::ErrorKind::NotFound.into(),
+                });
+            }
+            eprintln!("{} not found, using the defaults", shipped.display());
+        }
+
+        resolve(&docs, &root, expected)
+    }
+
+    /// Make sure the overrides file only contains known config files.
+    fn check_overrides(&self) -> Result<(), StartupError> {
+        let path = match &self.overrides {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        const EXPECTED: &str = "a struct with one field per config file";
+        let docs = [read(path)?];
+        let fields = match parse(&docs, 0, EXPECTED)? {
+            Node::Struct { name: None, fields } => fields,
+            _ => {
+                return Err(parse_error(
+                    &docs[0],
+                    0,
+                    "not a struct".to_string(),
+                    EXPECTED,
+                ));
+            }
+        };
+        match fields
+            .iter()
+            .find(|(key, _)| !Settings::FILES.contains(&key.text.as_str()))
+        {
+            Some((key, _)) => Err(parse_error(
+                &docs[0],
+                key.offset,
+                format!("unknown config file `{}`", key.text),
+                EXPECTED,
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Load a single file, that isn't layered.
+/// `expected` describes the content of the file for error messages.
+pub fn load_file<T: DeserializeOwned>(
+    path: &Path,
+    expected: &'static str,
+) -> Result<T, StartupError> {
+    let docs = [read(path)?];
+    let root = parse(&docs, 0, expected)?;
+    resolve(&docs, &root, expected)
+}
+
+/// Deserialize the merged tree of `docs`.
+fn resolve<T: DeserializeOwned>(
+    docs: &[Document],
+    root: &Node,
+    expected: &'static str,
+) -> Result<T, StartupError> {
+    let merged = Output::new(root);
+    deserialize(&merged.text).map_err(|error| {
+        // Point at the file the offending value comes from.
+        let (origin, message) = match error {
+            DeError::Syntax(offset, message) => (merged.origin(offset), message),
+            DeError::Value(offset, message) => (merged.value_origin(offset), message),
+        };
+        let (doc, offset) = origin.unwrap_or((0, 0));
+        let Document { path, source } = &docs[doc];
+        let (line, col) = position(source, offset);
+        StartupError::Parse {
+            path: path.clone(),
+            line,
+            col,
+            message,
+            expected,
+        }
+    })
+}
+
+/// The part of the overrides file, that applies to the config file `name`.
+fn override_field(root: Node, name: &str) -> Option<Node> {
+    match root {
+        Node::Struct { fields, .. } => fields
+            .into_iter()
+            .find(|(key, _)| key.text == name)
+            .map(|(_, node)| node),
+        _ => None,
+    }
+}
+
+fn read(path: &Path) -> Result<Document, StartupError> {
+    let source = fs::read_to_string(path).map_err(|error| StartupError::Read {
+        path: path.to_owned(),
+        error,
+    })?;
+    Ok(Document {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+fn parse(docs: &[Document], doc: usize, expected: &'static str) -> Result<Node, StartupError> {
+    tree::parse(&docs[doc].source, doc).map_err(|SyntaxError { offset, message }| {
+        parse_error(&docs[doc], offset, message.to_string(), expected)
+    })
+}
+
+fn parse_error(
+    doc: &Document,
+    offset: usize,
+    message: String,
+    expected: &'static str,
+) -> StartupError {
+    let (line, col) = position(&doc.source, offset);
+    StartupError::Parse {
+        path: doc.path.clone(),
+        line,
+        col,
+        message,
+        expected,
+    }
+}
+
+/// Errors of `deserialize` with the byte offset and the message.
+enum DeError {
+    /// The text at the offset isn't valid RON.
+    Syntax(usize, String),
+    /// The value ending at the offset doesn't fit the expected type.
+    Value(usize, String),
+}
+
+fn deserialize<T: DeserializeOwned>(source: &str) -> Result<T, DeError> {
+    let mut deserializer =
+        Deserializer::from_str(source).map_err(|e| DeError::Syntax(0, e.to_string()))?;
+    let value =
+        T::deserialize(&mut deserializer).and_then(|value| deserializer.end().map(|()| value));
+    value.map_err(|error| match error {
+        RonError::Parser(_, position) => {
+            // The message starts with the position, which is reported separately.
+            let prefix = format!("{}: ", position);
+            let message = error.to_string().trim_start_matches(&prefix).to_string();
+            DeError::Syntax(offset(source, position.line, position.col), message)
+        }
+        // Errors of the data types don't know their position, but the
+        // deserializer stopped right after the offending value.
+        RonError::Message(message) | RonError::IoError(message) => {
+            DeError::Value(source.len() - deserializer.remainder().len(), message)
+        }
+    })
+}
+
+/// Line and column, both starting at 1, of the byte `offset` in `source`.
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let consumed = &source[..offset.min(source.len())];
+    let line = consumed.matches('\n').count() + 1;
+    let col = consumed
+        .rsplit('\n')
+        .next()
+        .map_or(0, |last| last.chars().count())
+        + 1;
+    (line, col)
+}
+
+/// Byte offset of the line and column, both starting at 1, in `source`.
+fn offset(source: &str, line: usize, col: usize) -> usize {
+    let line_start: usize = source
+        .split('\n')
+        .take(line.saturating_sub(1))
+        .map(|line| line.len() + 1)
+        .sum();
+    (line_start + col.saturating_sub(1)).min(source.len())
+}
@@ -0,0 +1,451 @@
+//! A minimal syntax tree of RON documents, used to merge config files key by key.
+//!
+//! `ron::Value` can't be used for this, because it forgets the names of enum variants
+//! and where a value was written, which errors have to point at.
+//! `tests/config.rs` checks the merge against ron's own parser on the shipped files.
+//! Values, that aren't structs or maps, are kept as the text they were written as.
+
+/// Text copied verbatim from one of the merged documents.
+#[derive(Clone, Debug)]
+pub struct Raw {
+    pub text: String,
+    /// Index of the document the text comes from.
+    pub doc: usize,
+    /// Byte offset of the text in its document.
+    pub offset: usize,
+}
+
+#[derive(Clone, Debug)]
+pub enum Node {
+    /// A struct or struct variant, `Name(field: value, ...)`.
+    Struct {
+        name: Option<Raw>,
+        fields: Vec<(Raw, Node)>,
+    },
+    /// A map, `{key: value, ...}`.
+    Map(Vec<(Raw, Node)>),
+    /// Any other value, which is only ever replaced as a whole.
+    Value(Raw),
+}
+
+#[derive(Debug)]
+pub struct SyntaxError {
+    /// Byte offset of the error in the document.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+/// Parse the document `source`, that has the index `doc`.
+pub fn parse(source: &str, doc: usize) -> Result<Node, SyntaxError> {
+    let mut parser = Parser {
+        source,
+        doc,
+        pos: 0,
+    };
+    let node = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < source.len() {
+        return parser.error("trailing characters");
+    }
+    Ok(node)
+}
+
+/// Merge `over` into `base`. Fields and map entries of `over` replace
+/// those in `base` with the same key, everything else is kept.
+pub fn merge(base: Node, over: Node) -> Node {
+    match (base, over) {
+        (
+            Node::Struct { name, fields },
+            Node::Struct {
+                name: over_name,
+                fields: over_fields,
+            },
+        ) if same_name(&name, &over_name) => Node::Struct {
+            name: over_name.or(name),
+            fields: merge_entries(fields, over_fields),
+        },
+        (Node::Map(entries), Node::Map(over_entries)) => {
+            Node::Map(merge_entries(entries, over_entries))
+        }
+        (_, over) => over,
+    }
+}
+
+/// A struct without name may be any struct, a struct variant must stay the same variant.
+fn same_name(name: &Option<Raw>, other: &Option<Raw>) -> bool {
+    match (name, other) {
+        (Some(name), Some(other)) => name.text == other.text,
+        _ => true,
+    }
+}
+
+fn merge_entries(mut base: Vec<(Raw, Node)>, over: Vec<(Raw, Node)>) -> Vec<(Raw, Node)> {
+    for (key, value) in over {
+        match base
+            .iter()
+            .position(|(base_key, _)| base_key.text == key.text)
+        {
+            Some(i) => {
+                let (_, old) = base.remove(i);
+                base.insert(i, (key, merge(old, value)));
+            }
+            None => base.push((key, value)),
+        }
+    }
+    base
+}
+
+/// A merged document, that remembers where its parts were copied from.
+pub struct Output {
+    pub text: String,
+    segments: Vec<Segment>,
+}
+
+struct Segment {
+    /// Byte offset in the output.
+    start: usize,
+    len: usize,
+    doc: usize,
+    /// Byte offset in the original document.
+    offset: usize,
+}
+
+impl Output {
+    /// Write `node` as RON text.
+    pub fn new(node: &Node) -> Self {
+        let mut output = Output {
+            text: String::new(),
+            segments: Vec::new(),
+        };
+        output.write(node);
+        output
+    }
+
+    /// The document and the byte offset in it, that `offset` in the output was copied from.
+    /// Offsets between copied parts belong to the preceding part.
+    pub fn origin(&self, offset: usize) -> Option<(usize, usize)> {
+        let segment = self.segments.iter().rev().find(|s| s.start <= offset)?;
+        let within = (offset - segment.start).min(segment.len);
+        Some((segment.doc, segment.offset + within))
+    }
+
+    /// The document and the byte offset in it, where the value ending at `offset` in the
+    /// output was copied from.
+    pub fn value_origin(&self, offset: usize) -> Option<(usize, usize)> {
+        let segment = self.segments.iter().rev().find(|s| s.start < offset)?;
+        Some((segment.doc, segment.offset))
+    }
+
+    fn write(&mut self, node: &Node) {
+        match node {
+            Node::Struct { name, fields } => {
+                if let Some(name) = name {
+                    self.copy(name);
+                }
+                self.text.push('(');
+                self.write_entries(fields);
+                self.text.push(')');
+            }
+            Node::Map(entries) => {
+                self.text.push('{');
+                self.write_entries(entries);
+                self.text.push('}');
+            }
+            Node::Value(raw) => self.copy(raw),
+        }
+    }
+
+    fn write_entries(&mut self, entries: &[(Raw, Node)]) {
+        for (key, value) in entries {
+            self.copy(key);
+            self.text.push_str(": ");
+            self.write(value);
+            self.text.push_str(", ");
+        }
+    }
+
+    fn copy(&mut self, raw: &Raw) {
+        self.segments.push(Segment {
+            start: self.text.len(),
+            len: raw.text.len(),
+            doc: raw.doc,
+            offset: raw.offset,
+        });
+        self.text.push_str(&raw.text);
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    doc: usize,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn value(&mut self) -> Result<Node, SyntaxError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            None => self.error("unexpected end of file"),
+            Some(b'{') => {
+                self.pos += 1;
+                Ok(Node::Map(self.entries(b'}', false)?))
+            }
+            Some(b'(') if self.fields_follow(start + 1) => {
+                self.pos += 1;
+                Ok(Node::Struct {
+                    name: None,
+                    fields: self.entries(b')', true)?,
+                })
+            }
+            Some(b'(') | Some(b'[') => {
+                self.skip_brackets()?;
+                Ok(Node::Value(self.raw(start)))
+            }
+            Some(b'"') => {
+                self.skip_string()?;
+                Ok(Node::Value(self.raw(start)))
+            }
+            Some(b'r') if self.raw_string_follows() => {
+                self.skip_raw_string()?;
+                Ok(Node::Value(self.raw(start)))
+            }
+            Some(b'\'') => {
+                self.skip_char()?;
+                Ok(Node::Value(self.raw(start)))
+            }
+            Some(c) if is_ident_start(c) => {
+                self.skip_ident();
+                let name = self.raw(start);
+                let name_end = self.pos;
+                self.skip_whitespace();
+                if self.peek() != Some(b'(') {
+                    self.pos = name_end;
+                    Ok(Node::Value(name))
+                } else if self.fields_follow(self.pos + 1) {
+                    self.pos += 1;
+                    Ok(Node::Struct {
+                        name: Some(name),
+                        fields: self.entries(b')', true)?,
+                    })
+                } else {
+                    // Tuple variants and `Some(..)`
+                    self.skip_brackets()?;
+                    Ok(Node::Value(self.raw(start)))
+                }
+            }
+            Some(b',') | Some(b':') | Some(b')') | Some(b']') | Some(b'}') => {
+                self.error("expected a value")
+            }
+            Some(_) => {
+                // Numbers and the like
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_whitespace() || b",:)]}".contains(&c) {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                Ok(Node::Value(self.raw(start)))
+            }
+        }
+    }
+
+    /// Parse `key: value` entries up to `close`, the opening bracket is already consumed.
+    /// Struct fields have identifiers as keys, map keys may be any value.
+    fn entries(&mut self, close: u8, fields: bool) -> Result<Vec<(Raw, Node)>, SyntaxError> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(entries);
+            }
+            let start = self.pos;
+            if fields {
+                if !self.peek().map_or(false, is_ident_start) {
+                    return self.error("expected a field name");
+                }
+                self.skip_ident();
+            } else {
+                self.value()?;
+            }
+            let key = self.raw(start);
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return self.error("expected `:`");
+            }
+            self.pos += 1;
+            let value = self.value()?;
+            entries.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {}
+                _ if close == b')' => return self.error("expected `,` or `)`"),
+                _ => return self.error("expected `,` or `}`"),
+            }
+        }
+    }
+
+    /// Whether the brackets starting before `pos` contain struct fields, `(name: ...`.
+    fn fields_follow(&mut self, pos: usize) -> bool {
+        let saved = self.pos;
+        self.pos = pos;
+        self.skip_whitespace();
+        let follow = self.peek().map_or(false, is_ident_start) && {
+            self.skip_ident();
+            self.skip_whitespace();
+            self.peek() == Some(b':')
+        };
+        self.pos = saved;
+        follow
+    }
+
+    /// Skip a bracketed value including everything nested in it.
+    fn skip_brackets(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        let mut depth = 0;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => {
+                    self.pos = start;
+                    return self.error("unclosed bracket");
+                }
+                Some(b'(') | Some(b'[') | Some(b'{') => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                Some(b')') | Some(b']') | Some(b'}') => {
+                    depth -= 1;
+                    self.pos += 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(b'"') => self.skip_string()?,
+                Some(b'r') if self.raw_string_follows() => self.skip_raw_string()?,
+                Some(b'\'') => self.skip_char()?,
+                Some(_) => self.skip_one(),
+            }
+        }
+    }
+
+    fn skip_string(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                None => {
+                    self.pos = start;
+                    return self.error("unterminated string");
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    self.skip_one();
+                }
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(_) => self.skip_one(),
+            }
+        }
+    }
+
+    fn raw_string_follows(&self) -> bool {
+        let rest = &self.source.as_bytes()[self.pos + 1..];
+        let hashes = rest.iter().take_while(|&&c| c == b'#').count();
+        rest.get(hashes) == Some(&b'"')
+    }
+
+    /// Skip `r#"..."#` with any number of `#`.
+    fn skip_raw_string(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        let rest = &self.source[self.pos + 1..];
+        let hashes = rest.bytes().take_while(|&c| c == b'#').count();
+        let end = format!("\"{}", "#".repeat(hashes));
+        match rest[hashes + 1..].find(&end) {
+            Some(len) => {
+                self.pos += 1 + hashes + 1 + len + end.len();
+                Ok(())
+            }
+            None => {
+                self.pos = start;
+                self.error("unterminated string")
+            }
+        }
+    }
+
+    fn skip_char(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        self.pos += 1;
+        if self.peek() == Some(b'\\') {
+            self.pos += 1;
+        }
+        self.skip_one();
+        if self.peek() != Some(b'\'') {
+            self.pos = start;
+            return self.error("unterminated character");
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn skip_ident(&mut self) {
+        while self
+            .peek()
+            .map_or(false, |c| c == b'_' || c.is_ascii_alphanumeric())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.source[self.pos..];
+            if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if rest.starts_with("/*") {
+                self.pos += rest.find("*/").map_or(rest.len(), |end| end + 2);
+            } else if self.peek().map_or(false, |c| c.is_ascii_whitespace()) {
+                self.pos += 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Skip a single, possibly multibyte, character.
+    fn skip_one(&mut self) {
+        self.pos += self.source[self.pos..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.pos).copied()
+    }
+
+    /// The text from `start` up to the current position.
+    fn raw(&self, start: usize) -> Raw {
+        Raw {
+            text: self.source[start..self.pos].to_string(),
+            doc: self.doc,
+            offset: start,
+        }
+    }
+
+    fn error<T>(&self, message: &'static str) -> Result<T, SyntaxError> {
+        Err(SyntaxError {
+            offset: self.pos,
+            message,
+        })
+    }
+}
+
+fn is_ident_start(c: u8) -> bool {
+    c == b'_' || c.is_ascii_alphabetic()
+}
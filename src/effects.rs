@@ -0,0 +1,162 @@
+//! Visual effects: a trail behind the ball and sparks where it hits something.
+//! Effects are purely cosmetic, they never touch the state of the match.
+use amethyst::{
+    assets::Handle,
+    core::{math::Vector3, Hidden, Transform},
+    ecs::{
+        prelude::{Component, DenseVecStorage},
+        world::Builder,
+        World, WorldExt,
+    },
+    renderer::{palette::Srgba, resources::Tint, SpriteRender, SpriteSheet, Transparent},
+    utils::removal::Removal,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{states::State, theme::Theme};
+
+/// Number of pooled sparks, shared by all impacts.
+pub const PARTICLE_POOL_SIZE: usize = 64;
+/// Number of pooled trail segments, the longest possible trail.
+pub const TRAIL_SEGMENTS: usize = 10;
+
+/// Which effects are shown, loaded from `config/effects.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EffectsConfig {
+    /// A fading trail behind the ball, that grows with its speed.
+    pub trail: bool,
+    /// Sparks where the ball hits a paddle or a wall.
+    pub sparks: bool,
+    /// A burst of sparks when a point is scored.
+    pub bursts: bool,
+    /// Camera shake on hard paddle hits and on scoring.
+    /// Never shown with `AccessibilityConfig::reduce_motion`.
+    pub screen_shake: bool,
+    /// A short freeze of the gameplay when the ball hits a paddle.
+    pub hit_stop: bool,
+}
+
+impl Default for EffectsConfig {
+    fn default() -> Self {
+        EffectsConfig {
+            trail: true,
+            sparks: true,
+            bursts: true,
+            screen_shake: true,
+            hit_stop: true,
+        }
+    }
+}
+
+/// Something the ball hit, sent by `BounceSystem` and `WinnerSystem`
+/// through an `EventChannel<Impact>`.
+#[derive(Clone, Copy, Debug)]
+pub struct Impact {
+    pub kind: ImpactKind,
+    /// Where the ball was at the time of the impact.
+    pub position: Vector3<f32>,
+    /// Direction away from whatever the ball hit, into the arena.
+    pub normal: Vector3<f32>,
+    /// Speed of the ball right after the impact.
+    pub speed: f32,
+    /// Spin of the ball right after the impact, or when it left the arena.
+    pub spin: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImpactKind {
+    Wall,
+    Paddle,
+    /// The ball left the arena and a point was scored.
+    Score,
+}
+
+/// A pooled spark. Sparks that aren't flying are `Hidden`.
+pub struct Particle {
+    pub velocity: Vector3<f32>,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Particle {
+            velocity: Vector3::zeros(),
+            age: 0.0,
+            lifetime: 0.0,
+        }
+    }
+}
+
+impl Component for Particle {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A pooled piece of the ball's trail, the one with `index` 0 is closest to the ball.
+pub struct TrailSegment {
+    pub index: usize,
+}
+
+impl Component for TrailSegment {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Shakes the camera it's attached to, driven by `JuiceSystem`.
+pub struct CameraShake {
+    /// Position of the camera without shake.
+    pub rest: Vector3<f32>,
+    /// Strength of the shake, between 0 and 1. Decays over time.
+    pub trauma: f32,
+}
+
+impl CameraShake {
+    pub fn new(rest: Vector3<f32>) -> Self {
+        CameraShake { rest, trauma: 0.0 }
+    }
+}
+
+impl Component for CameraShake {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Create the pools of sparks and trail segments, that are removed with `state`.
+/// They are drawn with the ball sprite and start out hidden.
+pub fn initialize_effects(world: &mut World, sprite_sheet: Handle<SpriteSheet>, state: State) {
+    world.register::<Particle>();
+    world.register::<TrailSegment>();
+    world.register::<Tint>();
+    world.register::<Hidden>();
+
+    let sprite_render = SpriteRender {
+        sprite_sheet,
+        sprite_number: world
+            .entry::<Theme>()
+            .or_insert_with(Theme::default)
+            .sprites
+            .ball,
+    };
+    for _ in 0..PARTICLE_POOL_SIZE {
+        world
+            .create_entity()
+            .with(Particle::default())
+            .with(sprite_render.clone())
+            .with(Transform::default())
+            .with(Tint(Srgba::new(1.0, 1.0, 1.0, 1.0)))
+            .with(Transparent)
+            .with(Hidden)
+            .with(Removal::new(state))
+            .build();
+    }
+    for index in 0..TRAIL_SEGMENTS {
+        world
+            .create_entity()
+            .with(TrailSegment { index })
+            .with(sprite_render.clone())
+            .with(Transform::default())
+            .with(Tint(Srgba::new(1.0, 1.0, 1.0, 1.0)))
+            .with(Transparent)
+            .with(Hidden)
+            .with(Removal::new(state))
+            .build();
+    }
+}
@@ -0,0 +1,65 @@
+//! Errors that keep the game from starting.
+use std::{error::Error, fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum StartupError {
+    /// A config file could not be read.
+    Read { path: PathBuf, error: io::Error },
+    /// A config file is not valid RON or doesn't describe what the game expects.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        col: usize,
+        message: String,
+        /// Description of what the file should contain.
+        expected: &'static str,
+    },
+    /// The replay file passed with `--replay` could not be loaded.
+    Replay { path: PathBuf, error: io::Error },
+    /// The assets directory does not exist.
+    MissingAssets(PathBuf),
+    /// The engine failed to start.
+    Engine(amethyst::Error),
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartupError::Read { path, error } => {
+                write!(f, "Could not read {}: {}", path.display(), error)
+            }
+            StartupError::Parse {
+                path,
+                line,
+                col,
+                message,
+                expected,
+            } => write!(
+                f,
+                "{}:{}:{}: {}\n  expected {}",
+                path.display(),
+                line,
+                col,
+                message,
+                expected
+            ),
+            StartupError::Replay { path, error } => {
+                write!(f, "Could not load the replay {}: {}", path.display(), error)
+            }
+            StartupError::MissingAssets(path) => write!(
+                f,
+                "The assets directory {} does not exist. Use --assets-dir to point to it.",
+                path.display()
+            ),
+            StartupError::Engine(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for StartupError {}
+
+impl From<amethyst::Error> for StartupError {
+    fn from(error: amethyst::Error) -> Self {
+        StartupError::Engine(error)
+    }
+}
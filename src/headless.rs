@@ -0,0 +1,75 @@
+//! Matches without a window, rendering or audio.
+//! Used to benchmark the AI and to test the game logic.
+use amethyst::{
+    core::{timing::Time, transform::TransformBundle, SystemBundle},
+    ecs::{Builder, Dispatcher, World, WorldExt},
+};
+
+use crate::{
+    pong::{Ai, AiParams, GameRng, Paddle, ScoreBoard, Side, INITIAL_BALL_SPEED},
+    rules::{MatchClock, MatchPhase, MatchRules},
+    states::{ball_transform, game_dispatcher, paddle_transform, serve},
+};
+
+/// A match that is stepped by hand with a fixed time step.
+pub struct Simulation<'a, 'b> {
+    pub world: World,
+    dispatcher: Dispatcher<'a, 'b>,
+}
+
+impl<'a, 'b> Simulation<'a, 'b> {
+    /// Set up a match between the given AI controllers.
+    /// A side without AI gets a paddle, that only moves if something moves it.
+    pub fn new(
+        rules: &MatchRules,
+        left: Option<AiParams>,
+        right: Option<AiParams>,
+        seed: u64,
+    ) -> Self {
+        let mut world = World::new();
+        world.insert(GameRng::from_seed(seed));
+        world.insert(ScoreBoard::default());
+        world.insert(MatchClock::new(rules));
+
+        let mut builder = game_dispatcher(&mut world);
+        TransformBundle::new()
+            .build(&mut world, &mut builder)
+            .expect("Transform bundle builds");
+        let mut dispatcher = builder.build();
+        dispatcher.setup(&mut world);
+
+        let ball = serve(&mut world.write_resource::<GameRng>().0, INITIAL_BALL_SPEED);
+        world
+            .create_entity()
+            .with(ball)
+            .with(ball_transform())
+            .build();
+        for (side, ai) in vec![(Side::Left, left), (Side::Right, right)] {
+            let mut paddle = world
+                .create_entity()
+                .with(Paddle::new(side))
+                .with(paddle_transform(side));
+            if let Some(params) = ai {
+                paddle = paddle.with(Ai { params });
+            }
+            paddle.build();
+        }
+
+        Simulation { world, dispatcher }
+    }
+
+    /// Advance the match by `delta` seconds.
+    pub fn step(&mut self, delta: f32) {
+        self.world.write_resource::<Time>().set_delta_seconds(delta);
+        self.dispatcher.dispatch(&self.world);
+        self.world.maintain();
+    }
+
+    pub fn phase(&self) -> MatchPhase {
+        self.world.read_resource::<MatchClock>().phase
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.world.read_resource::<MatchClock>().is_finished()
+    }
+}
@@ -0,0 +1,201 @@
+//! Heads-up display on top of the match, that explains what the ball does.
+use amethyst::{
+    assets::{Handle, Loader},
+    core::{Hidden, Transform},
+    ecs::{
+        prelude::{Component, DenseVecStorage, Entity},
+        world::Builder,
+        World, WorldExt,
+    },
+    renderer::{palette::Srgba, resources::Tint, SpriteRender, SpriteSheet, Transparent},
+    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    utils::removal::Removal,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{pong::ScaledUi, states::State, theme::Theme};
+
+/// Number of pooled markers circling a spinning ball.
+pub const SPIN_MARKERS: usize = 3;
+/// Number of pooled dots of the curve preview.
+pub const PATH_DOTS: usize = 12;
+
+/// Which HUD elements are shown, loaded from `config/hud.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HudConfig {
+    /// Text at the bottom telling which way and how hard the ball curves.
+    pub spin_meter: bool,
+    /// Markers circling the ball in the direction of its spin.
+    pub spin_overlay: bool,
+    /// Dots along the predicted flight of the ball, in practice mode.
+    pub curve_preview: bool,
+    /// "3, 2, 1" in the middle of the arena, before the ball is served.
+    pub serve_countdown: bool,
+    /// Who scored the last point, until the next serve.
+    pub score_banner: bool,
+    /// Arrows next to the ball, pointing where it will be served.
+    pub serve_arrow: bool,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        HudConfig {
+            spin_meter: true,
+            spin_overlay: true,
+            curve_preview: true,
+            serve_countdown: true,
+            score_banner: true,
+            serve_arrow: true,
+        }
+    }
+}
+
+/// The ui texts in the middle of the arena, while the ball waits for the serve.
+pub struct CourtText {
+    pub countdown: Entity,
+    pub banner: Entity,
+    pub serve_arrow: Entity,
+}
+
+/// The text showing the spin of the ball.
+#[derive(Default)]
+pub struct SpinMeter;
+
+impl Component for SpinMeter {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A pooled marker circling the ball, the one with `index` 0 leads.
+pub struct SpinMarker {
+    pub index: usize,
+}
+
+impl Component for SpinMarker {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A pooled dot of the curve preview, the one with `index` 0 is closest to the ball.
+pub struct PathDot {
+    pub index: usize,
+}
+
+impl Component for PathDot {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Create the spin meter and the pooled markers, that are removed with `state`.
+/// The dots of the curve preview are only created with `curve_preview`.
+pub fn initialize_spin_hud(
+    world: &mut World,
+    sprite_sheet: Handle<SpriteSheet>,
+    state: State,
+    curve_preview: bool,
+) {
+    world.register::<SpinMeter>();
+    world.register::<SpinMarker>();
+    world.register::<PathDot>();
+    world.register::<ScaledUi>();
+    world.register::<Tint>();
+    world.register::<Hidden>();
+
+    let (font, color, ball) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (
+            theme.score_font.clone(),
+            theme.text_color,
+            theme.sprites.ball,
+        )
+    };
+    let font = world
+        .read_resource::<Loader>()
+        .load(font, TtfFormat, (), &world.read_resource());
+    let transform = UiTransform::new(
+        "SpinMeter".to_string(),
+        Anchor::BottomMiddle,
+        Anchor::BottomMiddle,
+        0.,
+        20.,
+        1.,
+        300.,
+        30.,
+    );
+    world
+        .create_entity()
+        .with(SpinMeter)
+        .with(ScaledUi::new(&transform, 20.))
+        .with(transform)
+        .with(UiText::new(font, String::new(), color, 20.))
+        .with(Removal::new(state))
+        .build();
+
+    let sprite_render = SpriteRender {
+        sprite_sheet,
+        sprite_number: ball,
+    };
+    for index in 0..SPIN_MARKERS {
+        world
+            .create_entity()
+            .with(SpinMarker { index })
+            .with(sprite_render.clone())
+            .with(Transform::default())
+            .with(Tint(Srgba::new(1.0, 1.0, 1.0, 1.0)))
+            .with(Transparent)
+            .with(Hidden)
+            .with(Removal::new(state))
+            .build();
+    }
+    if curve_preview {
+        for index in 0..PATH_DOTS {
+            world
+                .create_entity()
+                .with(PathDot { index })
+                .with(sprite_render.clone())
+                .with(Transform::default())
+                .with(Tint(Srgba::new(1.0, 1.0, 1.0, 1.0)))
+                .with(Transparent)
+                .with(Hidden)
+                .with(Removal::new(state))
+                .build();
+        }
+    }
+}
+
+/// Create the texts of the serve countdown, the score banner and the serve arrow,
+/// that are removed with `State::Game`.
+pub fn initialize_court_hud(world: &mut World) {
+    world.register::<ScaledUi>();
+    let (font, color) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (theme.score_font.clone(), theme.text_color)
+    };
+    let font = world
+        .read_resource::<Loader>()
+        .load(font, TtfFormat, (), &world.read_resource());
+
+    let mut text = |id: &str, y: f32, width: f32, height: f32, font_size: f32| {
+        let transform = UiTransform::new(
+            id.to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.,
+            y,
+            1.,
+            width,
+            height,
+        );
+        world
+            .create_entity()
+            .with(ScaledUi::new(&transform, font_size))
+            .with(transform)
+            .with(UiText::new(font.clone(), String::new(), color, font_size))
+            .with(Removal::new(State::Game))
+            .build()
+    };
+    // Above the ball, that waits in the middle
+    let court_text = CourtText {
+        countdown: text("Countdown", 50., 100., 70., 60.),
+        banner: text("Banner", 110., 480., 40., 30.),
+        serve_arrow: text("ServeArrow", 0., 100., 40., 30.),
+    };
+    world.insert(court_text);
+}
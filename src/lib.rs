@@ -0,0 +1,37 @@
+#![warn(rust_2018_idioms)]
+#![forbid(unsafe_code)]
+//! Pong Tutorial 1
+
+use amethyst::{ecs::prelude::Entity, ui::UiFinder};
+
+pub mod accessibility;
+pub mod achievements;
+pub mod attract;
+pub mod audio;
+pub mod campaign;
+pub mod cli;
+pub mod config;
+pub mod effects;
+pub mod error;
+pub mod headless;
+pub mod hud;
+pub mod locale;
+pub mod menu;
+pub mod paths;
+pub mod pause;
+pub mod pong;
+pub mod practice;
+pub mod profile;
+pub mod rating;
+pub mod replay;
+pub mod rules;
+pub mod save;
+pub mod states;
+pub mod systems;
+pub mod theme;
+pub mod tournament;
+
+/// Find's the UI Element by name
+fn find_ui(name: &'static str) -> impl FnOnce(UiFinder<'_>) -> Option<Entity> {
+    move |finder| finder.find(name)
+}
@@ -0,0 +1,85 @@
+//! Translations of the UI text.
+//!
+//! String tables live in `assets/locale/<language>.ron`, the language is picked in `config/appearance.ron`.
+//! UI prefabs refer to a string by its key with a leading `@`, e.g. `text: "@main_menu.quit"`,
+//! and to the font as `"@font"`. A language may bring its own font, which then replaces the
+//! default font of the prefabs and the font of the theme.
+use serde::{Deserialize, Serialize};
+
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    config,
+    error::StartupError,
+    theme::{fill_placeholders, Theme},
+};
+
+/// Language of the strings missing in the others.
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Font of the UI prefabs in languages without their own.
+const DEFAULT_FONT: &str = "font/square.ttf";
+
+/// The strings of a language.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Locale {
+    /// Font for scripts the default fonts don't cover, relative to the assets directory.
+    /// It's used for all text, in the menus as well as in the HUD.
+    #[serde(default)]
+    pub font: Option<String>,
+    pub strings: BTreeMap<String, String>,
+}
+
+impl Locale {
+    /// Load the strings of `language` from the `locale` directory of the assets,
+    /// filling in the ones it lacks from the fallback language.
+    pub fn load(assets_dir: &Path, language: &str) -> Result<Self, StartupError> {
+        let load = |language: &str| {
+            let path = assets_dir.join("locale").join(format!("{}.ron", language));
+            config::load_file::<Locale>(&path, "a string table with font and strings")
+        };
+        let mut locale = load(language)?;
+        if language != FALLBACK_LANGUAGE {
+            for (key, string) in load(FALLBACK_LANGUAGE)?.strings {
+                locale.strings.entry(key).or_insert(string);
+            }
+        }
+        Ok(locale)
+    }
+
+    /// Draw the text over the arena in the locale's font, if it has one.
+    pub fn adjust(&self, theme: &mut Theme) {
+        if let Some(font) = &self.font {
+            theme.score_font = font.clone();
+        }
+    }
+
+    /// The string `key` stands for, or the key itself if there is none.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map_or(key, String::as_str)
+    }
+
+    /// The string `key` stands for, with each `{}` replaced by the next of `values`.
+    pub fn format(&self, key: &str, values: &[&str]) -> String {
+        let mut values = values.iter();
+        let mut parts = self.get(key).split("{}");
+        let mut text = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            text.push_str(values.next().unwrap_or(&""));
+            text.push_str(part);
+        }
+        text
+    }
+
+    /// Replace the string keys of a UI prefab with their strings, and its font with the
+    /// locale's font if it has one. Unknown keys are kept.
+    pub fn localize(&self, prefab: &str) -> String {
+        fill_placeholders(prefab, |key| match key {
+            "font" => Some(format!(
+                "{:?}",
+                self.font.as_deref().unwrap_or(DEFAULT_FONT)
+            )),
+            key => self.strings.get(key).map(|string| format!("{:?}", string)),
+        })
+    }
+}
@@ -3,59 +3,102 @@
 //! Pong Tutorial 1
 
 use amethyst::{
+    assets::Loader,
     audio::{AudioBundle, DjSystem},
     core::transform::TransformBundle,
-    ecs::prelude::Entity,
     input::{InputBundle, StringBindings},
-    prelude::{GameDataBuilder, Application, SystemExt},
+    prelude::{Application, GameData, GameDataBuilder, SimpleState, SystemExt, World, WorldExt},
     renderer::{
         plugins::{RenderFlat2D, RenderToWindow},
         types::DefaultBackend,
         RenderingBundle,
     },
-    ui::{RenderUi, UiBundle, UiFinder},
+    ui::{RenderUi, UiBundle},
     utils::application_root_dir,
 };
-use ron::de::Result as RonDeResult;
-use serde::Deserialize;
-
-use std::fs::File;
-use std::path::Path;
-
-mod audio;
-mod pong;
-mod states;
-mod systems;
+use amethyst_pong::{
+    audio::Music,
+    cli::{Opt, StartMode},
+    config::{Layers, Settings},
+    error::StartupError,
+    locale::Locale,
+    paths,
+    pong::{GameRng, PausedOrRunning},
+    replay::Replay,
+    states::{GameState, MainMenuState},
+    systems::LetterboxSystem,
+    theme::{Theme, ThemedSource},
+};
+use structopt::StructOpt;
 
-use audio::Music;
-use pong::PausedOrRunning;
-use states::MainMenuState;
+use std::{path::PathBuf, process};
 
-fn main() -> amethyst::Result<()> {
+fn main() {
     // Initialize logger
     amethyst::start_logger(Default::default());
+    if let Err(error) = run(Opt::from_args()) {
+        eprintln!("Pong could not start:\n{}", error);
+        process::exit(1);
+    }
+}
+
+fn run(opt: Opt) -> Result<(), StartupError> {
     // Initialize display stuff
-    let app_root = application_root_dir()?;
+    let app_root = application_root_dir().map_err(amethyst::Error::from)?;
     // Define paths
-    let display_config_path = app_root.join("config").join("display.ron");
-    let background_color_config_path = app_root.join("config").join("bg.ron");
-    let binding_path = app_root.join("config").join("bindings.ron");
+    let config_dir = opt
+        .config_dir
+        .clone()
+        .unwrap_or_else(|| app_root.join("config"));
+    let assets_dir = opt
+        .assets_dir
+        .clone()
+        .unwrap_or_else(|| app_root.join("assets"));
+    if !assets_dir.is_dir() {
+        return Err(StartupError::MissingAssets(assets_dir));
+    }
 
-    let background_color: [f32; 4] =
-        ron_de(background_color_config_path).expect("Background color valid");
+    let layers = Layers {
+        shipped: config_dir,
+        user: paths::config_dir(),
+        overrides: opt.config.clone(),
+    };
+    let mut settings = Settings::load(&layers)?;
+    if let (Some(width), Some(height)) = (opt.width, opt.height) {
+        settings.display.dimensions = Some((width, height));
+    }
+    if opt.dump_config {
+        println!("{}", settings.dump());
+        return Ok(());
+    }
+    let Settings {
+        display,
+        appearance,
+        bindings,
+        rules,
+        practice,
+        campaign,
+        effects,
+        hud,
+        accessibility,
+        achievements,
+        attract,
+        menu,
+        pause,
+    } = settings;
+    let mut theme = Theme::load(&assets_dir, &appearance.theme)?;
+    accessibility.adjust(&mut theme);
+    let locale = Locale::load(&assets_dir, &appearance.language)?;
+    locale.adjust(&mut theme);
 
-    let input_bundle =
-        InputBundle::<StringBindings>::new().with_bindings_from_file(binding_path)?;
+    let input_bundle = InputBundle::<StringBindings>::new().with_bindings(bindings);
 
     let game_data = GameDataBuilder::default()
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
                 // The RenderToWindow plugin provides all the scaffolding for
                 // opening a window and drawing on it
-                .with_plugin(
-                    RenderToWindow::from_config_path(display_config_path)?
-                        .with_clear(background_color),
-                )
+                .with_plugin(RenderToWindow::from_config(display).with_clear(theme.background))
                 // RenderFlat2D plugin is used to render entities
                 // with a `SpriteRender` component.
                 .with_plugin(RenderFlat2D::default())
@@ -70,26 +113,79 @@ fn main() -> amethyst::Result<()> {
                 .pausable(PausedOrRunning::Running),
             "dj_system",
             &[],
-        );
+        )
+        .with(LetterboxSystem, "letterbox_system", &[]);
+
+    let difficulty = opt.difficulty;
+    let ai_params = difficulty.ai_params();
+    // Recorded matches need a known seed to be played back
+    let seed = match (opt.seed, &opt.record) {
+        (None, Some(_)) => Some(rand::random()),
+        (seed, _) => seed,
+    };
+    let rng = match seed {
+        Some(seed) => GameRng::from_seed(seed),
+        None => GameRng::default(),
+    };
+    let source = ThemedSource::new(&assets_dir, theme.buttons.clone(), locale.clone());
+    let setup = move |world: &mut World| {
+        // The menus are built with the theme's buttons
+        world.write_resource::<Loader>().set_default_source(source);
+        world.insert(theme);
+        world.insert(locale);
+        world.insert(rules);
+        world.insert(practice);
+        world.insert(campaign);
+        world.insert(effects);
+        world.insert(hud);
+        world.insert(accessibility);
+        world.insert(achievements);
+        world.insert(attract);
+        world.insert(menu);
+        world.insert(pause);
+        world.insert(ai_params);
+        world.insert(difficulty);
+        world.insert(rng);
+    };
 
     // GAME!
-    let assets_dir = app_root.join("assets");
-    let mut game = Application::new(assets_dir, MainMenuState::default(), game_data)?;
+    let mut game = match (opt.mode, opt.replay) {
+        (_, Some(path)) => {
+            let replay =
+                Replay::load(&path).map_err(|error| StartupError::Replay { path, error })?;
+            build_game(assets_dir, GameState::from_replay(replay), game_data, setup)?
+        }
+        (None, None) => build_game(assets_dir, MainMenuState::default(), game_data, setup)?,
+        (Some(mode), None) => {
+            let state = match mode {
+                StartMode::SinglePlayer => GameState::with_single_player(),
+                StartMode::TwoPlayers => GameState::with_two_players(),
+            };
+            let state = match (opt.record, seed) {
+                (Some(path), Some(seed)) => state.with_recording(path, seed),
+                _ => state,
+            };
+            build_game(assets_dir, state, game_data, setup)?
+        }
+    };
     game.run();
 
     Ok(())
 }
 
-fn ron_de<P, T>(path: P) -> RonDeResult<T>
+/// Builds the application, starting in `initial_state`.
+/// `setup` inserts the resources, that every state relies on.
+fn build_game<S, F>(
+    assets_dir: PathBuf,
+    initial_state: S,
+    game_data: GameDataBuilder<'static, 'static>,
+    setup: F,
+) -> amethyst::Result<Application<'static, GameData<'static, 'static>>>
 where
-    P: AsRef<Path>,
-    T: for<'de> Deserialize<'de>,
+    S: SimpleState + 'static,
+    F: FnOnce(&mut World),
 {
-    let file = File::open(path.as_ref()).expect("File not found");
-    ron::de::from_reader(&file)
-}
-
-/// Find's the UI Element by name
-fn find_ui(name: &'static str) -> impl FnOnce(UiFinder<'_>) -> Option<Entity> {
-    move |finder| finder.find(name)
+    let mut builder = Application::build(assets_dir, initial_state)?;
+    setup(&mut builder.world);
+    builder.build(game_data)
 }
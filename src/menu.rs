@@ -0,0 +1,15 @@
+//! Settings of the menus.
+use serde::{Deserialize, Serialize};
+
+/// Loaded from `config/menu.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MenuConfig {
+    /// Ask before quitting mid-match or leaving a match for the main menu.
+    pub confirm: bool,
+}
+
+impl Default for MenuConfig {
+    fn default() -> Self {
+        MenuConfig { confirm: true }
+    }
+}
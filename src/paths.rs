@@ -0,0 +1,70 @@
+//! Locations of files the game reads and writes.
+use amethyst::utils::application_root_dir;
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const APP_NAME: &str = "amethyst-pong";
+
+/// Directory for saved games, tournaments and the like.
+///
+/// This is the platform's user data directory (i.e. `$XDG_DATA_HOME/amethyst-pong`),
+/// falling back to `saves` next to the executable.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_NAME))
+        .or_else(|| application_root_dir().ok().map(|root| root.join("saves")))
+        .unwrap_or_else(|| PathBuf::from("saves"))
+}
+
+/// Directory for the user's config files, that override the shipped ones.
+///
+/// This is the platform's user config directory (i.e. `$XDG_CONFIG_HOME/amethyst-pong`).
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_NAME))
+}
+
+/// The file the running tournament is stored in.
+pub fn tournament_file() -> PathBuf {
+    data_dir().join("tournament.ron")
+}
+
+/// The file the campaign progress is stored in.
+pub fn campaign_file() -> PathBuf {
+    data_dir().join("campaign.ron")
+}
+
+/// The file a saved match is stored in.
+pub fn match_file() -> PathBuf {
+    data_dir().join("match.ron")
+}
+
+/// The file the unlocked achievements are stored in.
+pub fn achievements_file() -> PathBuf {
+    data_dir().join("achievements.ron")
+}
+
+/// The file the player profiles are stored in.
+pub fn profiles_file() -> PathBuf {
+    data_dir().join("profiles.ron")
+}
+
+/// Read a value from a RON file.
+pub fn load_ron<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> io::Result<T> {
+    let file = fs::File::open(path)?;
+    ron::de::from_reader(file).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write a value as a RON file, creating parent directories as needed.
+pub fn save_ron<T: Serialize, P: AsRef<Path>>(value: &T, path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let ron = ron::ser::to_string_pretty(value, Default::default())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, ron)
+}
@@ -0,0 +1,23 @@
+//! When matches pause and how they resume.
+use serde::{Deserialize, Serialize};
+
+/// Loaded from `config/pause.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PauseConfig {
+    /// Pause, when the window loses the focus.
+    pub on_focus_lost: bool,
+    /// Pause, when a gamepad is disconnected.
+    pub on_gamepad_disconnected: bool,
+    /// Seconds counted down after resuming, before the match goes on. `0.0` goes on at once.
+    pub resume_countdown: f32,
+}
+
+impl Default for PauseConfig {
+    fn default() -> Self {
+        PauseConfig {
+            on_focus_lost: true,
+            on_gamepad_disconnected: true,
+            resume_countdown: 3.0,
+        }
+    }
+}
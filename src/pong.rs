@@ -6,14 +6,25 @@ use amethyst::{
     },
     input::InputEvent,
     prelude::StateEvent,
+    ui::UiTransform,
     winit::{Event, WindowEvent},
 };
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
-use std::f32::consts::{FRAC_PI_4, PI};
+use std::{
+    f32::consts::{FRAC_PI_4, PI},
+    fmt,
+};
+
+use crate::pause::PauseConfig;
 
+/// Size of the arena in world units. The camera shows all of it undistorted,
+/// in any window and for any aspect ratio of the arena.
 pub const ARENA_HEIGHT: f32 = 100.0;
 pub const ARENA_WIDTH: f32 = 100.0;
+/// Arena height in pixels, that the layout of `ScaledUi` elements is made for.
+pub const HUD_REFERENCE_HEIGHT: f32 = 500.0;
 pub const INITIAL_BALL_SPEED: f32 = 65.0;
 pub const BOTTOM_OF_SCREEN: f32 = ARENA_HEIGHT - PADDLE_SIZE_COLLISION[1] * 0.5;
 pub const TOP_OF_SCREEN: f32 = PADDLE_SIZE_COLLISION[1] * 0.5;
@@ -33,19 +44,100 @@ pub const MAX_ROTATION_ON_COLLISION: f32 = 40.0 * 2.0 * PI / 360.0;
 pub const ROT_FACTOR: f32 = 0.3;
 pub const SPEED_ROT_FACTOR: f32 = 0.01;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Side {
     Left,
     Right,
 }
 
-#[derive(ComponentDer)]
-pub struct Ai;
+#[derive(ComponentDer, Default)]
+pub struct Ai {
+    pub params: AiParams,
+}
+
+/// Tuning of the AI controlled paddle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AiParams {
+    /// Acceleration per unit of distance between paddle and ball.
+    pub gain: f32,
+    pub max_acceleration: f32,
+    pub max_speed: f32,
+    /// Fraction of the velocity lost per second.
+    pub damping: f32,
+}
+
+/// Preset strengths of the AI opponent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn ai_params(self) -> AiParams {
+        match self {
+            Difficulty::Easy => AiParams {
+                gain: 50.0,
+                max_acceleration: 150.0,
+                max_speed: 80.0,
+                damping: 5.0,
+            },
+            Difficulty::Normal => AiParams::default(),
+            Difficulty::Hard => AiParams {
+                gain: 160.0,
+                max_acceleration: 450.0,
+                max_speed: 220.0,
+                damping: 4.0,
+            },
+        }
+    }
+
+    /// The fixed Elo rating of the AI, that players are rated against.
+    pub fn rating(self) -> f32 {
+        match self {
+            Difficulty::Easy => 900.0,
+            Difficulty::Normal => 1200.0,
+            Difficulty::Hard => 1500.0,
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Normal
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Normal => write!(f, "normal"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+impl Default for AiParams {
+    fn default() -> Self {
+        AiParams {
+            gain: 100.0,
+            max_acceleration: 300.0,
+            max_speed: 150.0,
+            damping: 5.0,
+        }
+    }
+}
 
 #[derive(PartialEq)]
 pub enum PausedOrRunning {
     Running,
     Paused,
+    /// Gameplay freezes for a moment after a paddle hit, while the camera keeps shaking.
+    HitStop,
 }
 
 impl Default for PausedOrRunning {
@@ -69,17 +161,98 @@ pub struct Ball {
     pub rot_velocity: f32,
 }
 
+/// The random number generator used for serves and launches.
+/// Seed it to make matches reproducible.
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        GameRng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng(StdRng::from_entropy())
+    }
+}
+
 /// ScoreBoard contains the actual score data
-#[derive(Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ScoreBoard {
     pub score_left: i32,
     pub score_right: i32,
 }
 
+/// Names of the players of the running match, as shown to them.
+pub struct PlayerNames {
+    pub left: String,
+    pub right: String,
+}
+
+impl PlayerNames {
+    pub fn name(&self, side: Side) -> &str {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+        }
+    }
+}
+
+impl Default for PlayerNames {
+    fn default() -> Self {
+        PlayerNames {
+            left: "P1".to_string(),
+            right: "P2".to_string(),
+        }
+    }
+}
+
 /// ScoreText contains the ui text components that display the score
 pub struct ScoreText {
     pub p1_score: Entity,
     pub p2_score: Entity,
+    pub clock: Entity,
+}
+
+/// A camera showing the arena, fitted to the window by `LetterboxSystem`.
+#[derive(ComponentDer, Default)]
+pub struct ArenaCamera;
+
+/// Layout of a HUD element in pixels, for an arena `HUD_REFERENCE_HEIGHT` pixels high.
+/// `LetterboxSystem` scales it with the arena on screen.
+#[derive(ComponentDer, Clone, Debug)]
+pub struct ScaledUi {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub font_size: f32,
+}
+
+impl ScaledUi {
+    /// The layout of `transform` with `font_size`, both at the reference height.
+    pub fn new(transform: &UiTransform, font_size: f32) -> Self {
+        ScaledUi {
+            x: transform.local_x,
+            y: transform.local_y,
+            width: transform.width,
+            height: transform.height,
+            font_size,
+        }
+    }
+}
+
+/// Width and height of the view, that shows the whole arena at the `aspect` ratio
+/// (width / height) of the window. The remaining space is filled with letterbox bars.
+pub fn arena_view(aspect: f32) -> (f32, f32) {
+    if aspect > ARENA_WIDTH / ARENA_HEIGHT {
+        // Pillarbox: bars on the left and right
+        (ARENA_HEIGHT * aspect, ARENA_HEIGHT)
+    } else {
+        // Letterbox: bars above and below
+        (ARENA_WIDTH, ARENA_WIDTH / aspect)
+    }
 }
 
 impl Component for Ball {
@@ -101,21 +274,54 @@ impl Component for Paddle {
     type Storage = DenseVecStorage<Self>;
 }
 
-pub fn pause_requested(event: &StateEvent) -> bool {
+/// Whether the event pauses a match, on the pause action or as configured.
+pub fn pause_requested(event: &StateEvent, config: &PauseConfig) -> bool {
     use InputEvent::*;
     use StateEvent::*;
     match event {
         Input(ActionPressed(action)) if action == "pause" => true,
+        Input(ControllerDisconnected { .. }) => config.on_gamepad_disconnected,
         Window(Event::WindowEvent {
             window_id: _,
             event: WindowEvent::Focused(false),
-        }) => true,
+        }) => config.on_focus_lost,
         _ => false,
     }
 }
 
-pub fn random_45_vec(base: &Unit<Vector3<f32>>, norm: f32) -> Vector3<f32> {
-    let mut rng = thread_rng();
+/// The `velocity` of a ball with spin, after flying for `delta` seconds.
+pub fn curve(velocity: Vector3<f32>, rot_velocity: f32, delta: f32) -> Vector3<f32> {
+    let speed_rot =
+        Rotation::from_axis_angle(&Vector3::z_axis(), SPEED_ROT_FACTOR * rot_velocity * delta);
+    speed_rot * velocity
+}
+
+/// Positions of a ball in free flight, `steps` frames of `delta` seconds apart,
+/// moved like `MoveBallsSystem` does. Ends early, once the ball leaves the arena.
+pub fn predict_path(
+    mut position: Vector3<f32>,
+    mut velocity: Vector3<f32>,
+    rot_velocity: f32,
+    delta: f32,
+    steps: usize,
+) -> Vec<Vector3<f32>> {
+    let mut path = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        velocity = curve(velocity, rot_velocity, delta);
+        position += velocity * delta;
+        if position.x < 0.0
+            || position.x > ARENA_WIDTH
+            || position.y < 0.0
+            || position.y > ARENA_HEIGHT
+        {
+            break;
+        }
+        path.push(position);
+    }
+    path
+}
+
+pub fn random_45_vec<R: Rng>(rng: &mut R, base: &Unit<Vector3<f32>>, norm: f32) -> Vector3<f32> {
     let angle = rng.gen_range(-FRAC_PI_4, FRAC_PI_4);
     let rotation = Rotation::from_axis_angle(&Vector3::z_axis(), angle);
     norm * (rotation * base.into_inner())
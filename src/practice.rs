@@ -0,0 +1,229 @@
+//! Practice mode: drills against a wall or a ball launcher.
+use amethyst::{
+    core::{
+        math::{Rotation, Vector3},
+        Transform,
+    },
+    ecs::prelude::Entity,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    locale::Locale,
+    pong::{Ball, ARENA_HEIGHT, ARENA_WIDTH, PADDLE_WALL_OFFSET},
+};
+
+/// Pause between a ball leaving the arena and the next launch.
+pub const LAUNCH_DELAY: f32 = 0.5;
+
+/// Practice settings, loaded from `config/practice.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PracticeConfig {
+    pub opponent: Opponent,
+    pub launcher: Launcher,
+    pub drills: Vec<Drill>,
+}
+
+/// What the player is playing against.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Opponent {
+    /// The ball bounces off the right wall and the rally continues.
+    Wall,
+    /// Every ball is fired fresh from the right side.
+    Launcher,
+}
+
+/// How balls are fired.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Launcher {
+    pub speed: f32,
+    /// Angle in degrees above the horizontal.
+    pub angle: f32,
+    /// Random deviation from `angle` in degrees, in both directions.
+    pub angle_spread: f32,
+    /// Initial `Ball::rot_velocity`.
+    pub spin: f32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Corner {
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Drill {
+    /// Return the given number of balls without a miss.
+    ReturnStreak(u32),
+    /// Return the ball into a target of the given height in a corner of the right wall.
+    HitTarget { corner: Corner, size: f32 },
+}
+
+/// PracticeText contains the ui text component that displays the drill progress
+pub struct PracticeText {
+    pub status: Entity,
+}
+
+/// Practice keeps track of the current drill and the player's progress.
+pub struct Practice {
+    pub config: PracticeConfig,
+    pub drill: usize,
+    pub streak: u32,
+    pub best: u32,
+    pub successes: u32,
+}
+
+impl Default for PracticeConfig {
+    fn default() -> Self {
+        PracticeConfig {
+            opponent: Opponent::Launcher,
+            launcher: Launcher {
+                speed: 65.0,
+                angle: 0.0,
+                angle_spread: 20.0,
+                spin: 0.0,
+            },
+            drills: vec![Drill::ReturnStreak(20)],
+        }
+    }
+}
+
+impl Drill {
+    /// Vertical range of the target, if this drill has one.
+    pub fn target(&self) -> Option<(f32, f32)> {
+        match self {
+            Drill::HitTarget {
+                corner: Corner::Top,
+                size,
+            } => Some((ARENA_HEIGHT - size, ARENA_HEIGHT)),
+            Drill::HitTarget {
+                corner: Corner::Bottom,
+                size,
+            } => Some((0.0, *size)),
+            Drill::ReturnStreak(_) => None,
+        }
+    }
+
+    /// What the player has to do, as shown in the HUD.
+    pub fn display(&self, locale: &Locale) -> String {
+        match self {
+            Drill::ReturnStreak(n) => locale.format("practice.return_streak", &[&n.to_string()]),
+            Drill::HitTarget {
+                corner: Corner::Top,
+                ..
+            } => locale.get("practice.top_corner").to_string(),
+            Drill::HitTarget {
+                corner: Corner::Bottom,
+                ..
+            } => locale.get("practice.bottom_corner").to_string(),
+        }
+    }
+}
+
+impl Practice {
+    pub fn new(config: PracticeConfig) -> Self {
+        Practice {
+            config,
+            drill: 0,
+            streak: 0,
+            best: 0,
+            successes: 0,
+        }
+    }
+
+    pub fn current_drill(&self) -> Option<&Drill> {
+        self.config.drills.get(self.drill)
+    }
+
+    /// Switch to the next drill, starting from scratch.
+    pub fn next_drill(&mut self) {
+        if !self.config.drills.is_empty() {
+            self.drill = (self.drill + 1) % self.config.drills.len();
+        }
+        self.reset();
+    }
+
+    /// Start the current drill over.
+    pub fn reset(&mut self) {
+        self.streak = 0;
+        self.best = 0;
+        self.successes = 0;
+    }
+
+    /// The player missed the ball.
+    pub fn on_miss(&mut self) {
+        self.streak = 0;
+    }
+
+    /// The ball reached the right wall at height `y` after the player returned it.
+    pub fn on_return(&mut self, y: f32) {
+        match self.current_drill() {
+            Some(Drill::ReturnStreak(goal)) => {
+                let goal = *goal;
+                self.streak += 1;
+                if self.streak >= goal {
+                    self.successes += 1;
+                    self.best = self.best.max(self.streak);
+                    self.streak = 0;
+                }
+            }
+            Some(drill) => match drill.target() {
+                Some((low, high)) if y >= low && y <= high => {
+                    self.successes += 1;
+                    self.streak += 1;
+                }
+                _ => self.streak = 0,
+            },
+            None => self.streak += 1,
+        }
+        self.best = self.best.max(self.streak);
+    }
+
+    /// The text shown in the HUD.
+    pub fn status(&self, locale: &Locale) -> String {
+        let (streak, best, successes) = (
+            self.streak.to_string(),
+            self.best.to_string(),
+            self.successes.to_string(),
+        );
+        let drill = match self.current_drill() {
+            Some(drill) => drill,
+            None => return locale.format("practice.streak", &[&streak, &best]),
+        };
+        let progress = match drill {
+            Drill::ReturnStreak(goal) => locale.format(
+                "practice.streak_progress",
+                &[&streak, &goal.to_string(), &successes],
+            ),
+            Drill::HitTarget { .. } => {
+                locale.format("practice.target_progress", &[&successes, &streak, &best])
+            }
+        };
+        locale.format("practice.status", &[&drill.display(locale), &progress])
+    }
+
+    /// Launch the ball at the player according to the launcher settings.
+    pub fn launch<R: Rng>(&self, rng: &mut R, ball: &mut Ball, transform: &mut Transform) {
+        let launcher = &self.config.launcher;
+        let x = match self.config.opponent {
+            Opponent::Launcher => ARENA_WIDTH - PADDLE_WALL_OFFSET - ball.radius,
+            Opponent::Wall => ARENA_WIDTH / 2.0,
+        };
+        transform.set_translation_x(x);
+        transform.set_translation_y(ARENA_HEIGHT / 2.0);
+
+        let spread = launcher.angle_spread.abs();
+        let deviation = if spread > 0.0 {
+            rng.gen_range(-spread, spread)
+        } else {
+            0.0
+        };
+        // Positive angles point upwards, towards the player on the left.
+        let angle = -(launcher.angle + deviation).to_radians();
+        let rotation = Rotation::from_axis_angle(&Vector3::z_axis(), angle);
+        ball.velocity = rotation * Vector3::new(-launcher.speed, 0.0, 0.0);
+        ball.rot_velocity = launcher.spin;
+        ball.hidden = Some(LAUNCH_DELAY);
+    }
+}
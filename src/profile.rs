@@ -0,0 +1,183 @@
+//! Local player profiles with their preferences and statistics.
+use amethyst::input::Axis;
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::Path};
+
+use crate::{
+    paths::{load_ron, save_ron},
+    pong::{ScoreBoard, Side},
+    rating::INITIAL_RATING,
+};
+
+/// Avatars to choose from, relative to the assets directory.
+pub const AVATARS: [&str; 6] = [
+    "avatars/circle.png",
+    "avatars/square.png",
+    "avatars/triangle.png",
+    "avatars/diamond.png",
+    "avatars/cross.png",
+    "avatars/ring.png",
+];
+
+/// Paddle colours to choose from, `None` keeps the colour of the theme.
+pub const PADDLE_COLORS: [Option<[f32; 4]>; 6] = [
+    None,
+    Some([1.0, 0.3, 0.3, 1.0]),
+    Some([0.3, 1.0, 0.4, 1.0]),
+    Some([0.3, 0.6, 1.0, 1.0]),
+    Some([1.0, 0.9, 0.2, 1.0]),
+    Some([1.0, 0.4, 1.0, 1.0]),
+];
+
+/// A player known to this computer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// One of `AVATARS`.
+    pub avatar: String,
+    /// Tint of the player's paddle, `None` keeps the colour of the theme.
+    #[serde(default)]
+    pub paddle_color: Option<[f32; 4]>,
+    /// Keys to move the paddle with, replacing the configured binding of the side played on,
+    /// e.g. `Emulated(pos: Key(I), neg: Key(K))`.
+    #[serde(default)]
+    pub controls: Option<Axis>,
+    #[serde(default)]
+    pub stats: Stats,
+    /// Elo rating, see `rating`.
+    #[serde(default = "initial_rating")]
+    pub rating: f32,
+}
+
+fn initial_rating() -> f32 {
+    INITIAL_RATING
+}
+
+impl Profile {
+    pub fn new(name: String) -> Self {
+        Profile {
+            name,
+            avatar: AVATARS[0].to_string(),
+            paddle_color: None,
+            controls: None,
+            stats: Stats::default(),
+            rating: INITIAL_RATING,
+        }
+    }
+
+    /// Switch to the avatar after the current one.
+    pub fn next_avatar(&mut self) {
+        let current = AVATARS.iter().position(|avatar| *avatar == self.avatar);
+        let next = current.map_or(0, |current| (current + 1) % AVATARS.len());
+        self.avatar = AVATARS[next].to_string();
+    }
+
+    /// Switch to the paddle colour after the current one.
+    pub fn next_paddle_color(&mut self) {
+        let current = PADDLE_COLORS
+            .iter()
+            .position(|color| *color == self.paddle_color);
+        let next = current.map_or(0, |current| (current + 1) % PADDLE_COLORS.len());
+        self.paddle_color = PADDLE_COLORS[next];
+    }
+}
+
+/// Results of all matches a profile played.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub matches: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub points_won: u32,
+    pub points_lost: u32,
+}
+
+impl Stats {
+    /// Count a finished match, that was played on `side`.
+    pub fn record(&mut self, side: Side, scores: &ScoreBoard, winner: Side) {
+        let (own, other) = match side {
+            Side::Left => (scores.score_left, scores.score_right),
+            Side::Right => (scores.score_right, scores.score_left),
+        };
+        self.matches += 1;
+        if winner == side {
+            self.wins += 1;
+        } else {
+            self.losses += 1;
+        }
+        self.points_won += own.max(0) as u32;
+        self.points_lost += other.max(0) as u32;
+    }
+}
+
+/// All profiles, stored in the user data directory.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    /// Load the profiles, no profiles if there is no readable file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        load_ron(path).unwrap_or_default()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_ron(self, path)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Profile> {
+        self.profiles
+            .iter_mut()
+            .find(|profile| profile.name == name)
+    }
+
+    /// The profile with the given name, created if there is none yet.
+    pub fn get_or_create(&mut self, name: &str) -> &mut Profile {
+        match self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+        {
+            Some(index) => &mut self.profiles[index],
+            None => {
+                self.profiles.push(Profile::new(name.to_string()));
+                self.profiles.last_mut().expect("Just pushed")
+            }
+        }
+    }
+
+    /// Name of the profile after the one named `current`, in the order they were created.
+    pub fn next_name(&self, current: &str) -> Option<&str> {
+        let next = match self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == current)
+        {
+            Some(index) => (index + 1) % self.profiles.len(),
+            None => 0,
+        };
+        self.profiles.get(next).map(|profile| profile.name.as_str())
+    }
+}
+
+/// The profiles playing a match, `None` for a side without one, like the AI.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lineup {
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl Lineup {
+    pub fn name(&self, side: Side) -> Option<&str> {
+        match side {
+            Side::Left => self.left.as_deref(),
+            Side::Right => self.right.as_deref(),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Elo ratings of the players with a profile, rated against each other and the AI levels.
+use crate::{
+    locale::Locale,
+    pong::{Difficulty, Side},
+    profile::{Profiles, Stats},
+};
+
+/// Rating of a new profile.
+pub const INITIAL_RATING: f32 = 1200.0;
+/// The most a single match can change a rating by.
+pub const K_FACTOR: f32 = 32.0;
+
+/// Chance of a player rated `rating` to beat one rated `opponent`.
+pub fn expected_score(rating: f32, opponent: f32) -> f32 {
+    1.0 / (1.0 + 10f32.powf((opponent - rating) / 400.0))
+}
+
+/// What a match is worth to the player on `side`: 1 for a win, 0 for a loss.
+pub fn match_score(side: Side, winner: Side) -> f32 {
+    if winner == side {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// How much the `rating` of the player on `side` changes by a match against `opponent`.
+pub fn rating_change(rating: f32, opponent: f32, side: Side, winner: Side) -> f32 {
+    K_FACTOR * (match_score(side, winner) - expected_score(rating, opponent))
+}
+
+/// A line of the leaderboard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub rating: f32,
+    /// Results of the matches played, `None` for the AI levels.
+    pub stats: Option<Stats>,
+}
+
+/// All profiles and AI levels, the best rated first.
+pub fn leaderboard(profiles: &Profiles, locale: &Locale) -> Vec<Standing> {
+    let players = profiles.profiles.iter().map(|profile| Standing {
+        name: profile.name.clone(),
+        rating: profile.rating,
+        stats: Some(profile.stats.clone()),
+    });
+    let ais = Difficulty::ALL.iter().map(|difficulty| {
+        let level = locale.get(match difficulty {
+            Difficulty::Easy => "difficulty.easy",
+            Difficulty::Normal => "difficulty.normal",
+            Difficulty::Hard => "difficulty.hard",
+        });
+        Standing {
+            name: locale.format("leaderboard.ai", &[level]),
+            rating: difficulty.rating(),
+            stats: None,
+        }
+    });
+    let mut standings = players.chain(ais).collect::<Vec<_>>();
+    standings.sort_by(|a, b| {
+        b.rating
+            .partial_cmp(&a.rating)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    standings
+}
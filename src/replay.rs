@@ -0,0 +1,111 @@
+//! Recording matches started from the command line and playing them back.
+use amethyst::ecs::{World, WorldExt};
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::Path};
+
+use crate::{
+    accessibility::AccessibilityConfig,
+    effects::EffectsConfig,
+    paths::{load_ron, save_ron},
+    pong::{Difficulty, Side},
+    rules::MatchRules,
+    states::Mode,
+};
+
+/// Everything needed to play a match again: how it was set up and the input of every frame.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub mode: Mode,
+    /// Seed of the serves.
+    pub seed: u64,
+    /// Strength of the AI opponent in single player matches.
+    pub difficulty: Difficulty,
+    pub rules: MatchRules,
+    pub settings: PlaySettings,
+    pub frames: Vec<Frame>,
+}
+
+/// The settings outside of the rules, that change how the ball moves.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaySettings {
+    /// `AccessibilityConfig::ball_scale`
+    pub ball_scale: f32,
+    /// `EffectsConfig::hit_stop`
+    pub hit_stop: bool,
+}
+
+impl PlaySettings {
+    /// The settings in use.
+    pub fn current(world: &mut World) -> Self {
+        let ball_scale = world
+            .entry::<AccessibilityConfig>()
+            .or_insert_with(AccessibilityConfig::default)
+            .ball_scale;
+        let hit_stop = world
+            .entry::<EffectsConfig>()
+            .or_insert_with(EffectsConfig::default)
+            .hit_stop;
+        PlaySettings {
+            ball_scale,
+            hit_stop,
+        }
+    }
+
+    /// Use these settings instead of the configured ones, which are returned.
+    pub fn apply(self, world: &mut World) -> Self {
+        let configured = PlaySettings::current(world);
+        world.write_resource::<AccessibilityConfig>().ball_scale = self.ball_scale;
+        world.write_resource::<EffectsConfig>().hit_stop = self.hit_stop;
+        configured
+    }
+}
+
+/// One frame of a recorded match.
+/// While a replay is played back, the frame being played is a resource, its paddle input
+/// replaces the players'.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Game time that passed in the frame, in seconds.
+    pub delta: f32,
+    /// Values of the paddle axes, `None` without input.
+    pub left_paddle: Option<f32>,
+    pub right_paddle: Option<f32>,
+}
+
+impl Frame {
+    pub fn axis(&self, side: Side) -> Option<f32> {
+        match side {
+            Side::Left => self.left_paddle,
+            Side::Right => self.right_paddle,
+        }
+    }
+}
+
+impl Replay {
+    /// A replay without any frames yet.
+    pub fn new(
+        mode: Mode,
+        seed: u64,
+        difficulty: Difficulty,
+        rules: MatchRules,
+        settings: PlaySettings,
+    ) -> Self {
+        Replay {
+            mode,
+            seed,
+            difficulty,
+            rules,
+            settings,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        load_ron(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_ron(self, path)
+    }
+}
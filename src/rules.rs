@@ -0,0 +1,154 @@
+//! Match rules and the match clock.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    locale::Locale,
+    pong::{ScoreBoard, Side},
+};
+
+/// The rules a single match is played by.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchRules {
+    /// Length of regular time in seconds. `None` plays an endless match.
+    pub duration: Option<f32>,
+    /// What happens if the score is tied when the clock runs out.
+    pub tie_break: TieBreak,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// The next point wins.
+    SuddenDeath,
+    /// Play another `duration` seconds with the ball sped up by `speed_factor`.
+    /// If it's still a tie afterwards, sudden death decides.
+    Overtime { duration: f32, speed_factor: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MatchPhase {
+    Regular,
+    Overtime,
+    SuddenDeath,
+    /// The match is over, won by the side.
+    Finished(Side),
+}
+
+/// MatchClock keeps track of the remaining time and the phase of the match.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchClock {
+    pub remaining: Option<f32>,
+    pub phase: MatchPhase,
+    pub ball_speed_factor: f32,
+    tie_break: TieBreak,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            duration: None,
+            tie_break: TieBreak::SuddenDeath,
+        }
+    }
+}
+
+impl Default for MatchClock {
+    fn default() -> Self {
+        MatchClock::new(&MatchRules::default())
+    }
+}
+
+impl MatchClock {
+    pub fn new(rules: &MatchRules) -> Self {
+        MatchClock {
+            remaining: rules.duration,
+            phase: MatchPhase::Regular,
+            ball_speed_factor: 1.0,
+            tie_break: rules.tie_break.clone(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self.phase {
+            MatchPhase::Finished(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Advance the clock by `delta` seconds.
+    /// Returns true, if the ball speed factor changed this tick.
+    pub fn tick(&mut self, delta: f32, scores: &ScoreBoard) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        let remaining = match self.remaining.as_mut() {
+            Some(remaining) => remaining,
+            None => return false,
+        };
+        *remaining -= delta;
+        if *remaining > 0.0 {
+            return false;
+        }
+        *remaining = 0.0;
+        if let Some(leader) = leader(scores) {
+            self.phase = MatchPhase::Finished(leader);
+            return false;
+        }
+        match (self.phase, &self.tie_break) {
+            (
+                MatchPhase::Regular,
+                TieBreak::Overtime {
+                    duration,
+                    speed_factor,
+                },
+            ) => {
+                self.remaining = Some(*duration);
+                self.phase = MatchPhase::Overtime;
+                self.ball_speed_factor *= *speed_factor;
+                true
+            }
+            _ => {
+                self.remaining = None;
+                self.phase = MatchPhase::SuddenDeath;
+                false
+            }
+        }
+    }
+
+    /// Needs to be called whenever a player scored.
+    pub fn on_score(&mut self, scores: &ScoreBoard) {
+        if self.phase == MatchPhase::SuddenDeath {
+            if let Some(leader) = leader(scores) {
+                self.phase = MatchPhase::Finished(leader);
+            }
+        }
+    }
+
+    /// The text that is shown in the HUD.
+    pub fn display(&self, locale: &Locale) -> String {
+        match (self.phase, self.remaining) {
+            (MatchPhase::SuddenDeath, _) => locale.get("hud.sudden_death").to_string(),
+            (MatchPhase::Overtime, Some(remaining)) => {
+                locale.format("hud.overtime", &[&format_time(remaining)])
+            }
+            (_, Some(remaining)) => format_time(remaining),
+            (_, None) => String::new(),
+        }
+    }
+}
+
+/// The side that is currently ahead, if any.
+pub fn leader(scores: &ScoreBoard) -> Option<Side> {
+    if scores.score_left > scores.score_right {
+        Some(Side::Left)
+    } else if scores.score_right > scores.score_left {
+        Some(Side::Right)
+    } else {
+        None
+    }
+}
+
+/// Format seconds as `m:ss`, rounding up, so that `0:00` means the time is up.
+fn format_time(seconds: f32) -> String {
+    let seconds = seconds.max(0.0).ceil() as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,130 @@
+//! Saving an in-progress match and restoring it later.
+use amethyst::{
+    core::{math::Vector3, Transform},
+    ecs::{Join, World, WorldExt},
+    ui::UiText,
+};
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::Path};
+
+use crate::{
+    paths::{load_ron, save_ron},
+    pong::{Ball, Paddle, ScoreBoard, ScoreText, Side},
+    profile::Lineup,
+    rules::MatchClock,
+    states::Mode,
+};
+
+/// Everything needed to continue a match exactly where it stopped.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedMatch {
+    pub mode: Mode,
+    pub clock: MatchClock,
+    pub scores: ScoreBoard,
+    pub balls: Vec<SavedBall>,
+    pub paddles: Vec<SavedPaddle>,
+    /// The profiles playing the match, missing in matches saved before there were profiles.
+    #[serde(default)]
+    pub lineup: Lineup,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedBall {
+    pub translation: [f32; 3],
+    /// Rotation around the z axis in radians.
+    pub rotation: f32,
+    pub velocity: [f32; 3],
+    pub rot_velocity: f32,
+    pub hidden: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedPaddle {
+    pub side: Side,
+    pub y: f32,
+    pub velocity: f32,
+}
+
+impl SavedMatch {
+    /// Take a snapshot of the match that is currently played in `world`.
+    pub fn capture(world: &World, mode: Mode) -> Self {
+        let transforms = world.read_storage::<Transform>();
+        let balls = (&world.read_storage::<Ball>(), &transforms)
+            .join()
+            .map(|(ball, transform)| {
+                let translation = transform.translation();
+                SavedBall {
+                    translation: [translation.x, translation.y, translation.z],
+                    rotation: transform.euler_angles().2,
+                    velocity: [ball.velocity.x, ball.velocity.y, ball.velocity.z],
+                    rot_velocity: ball.rot_velocity,
+                    hidden: ball.hidden,
+                }
+            })
+            .collect();
+        let paddles = (&world.read_storage::<Paddle>(), &transforms)
+            .join()
+            .map(|(paddle, transform)| SavedPaddle {
+                side: paddle.side,
+                y: transform.translation().y,
+                velocity: paddle.velocity,
+            })
+            .collect();
+        SavedMatch {
+            mode,
+            clock: (*world.read_resource::<MatchClock>()).clone(),
+            scores: (*world.read_resource::<ScoreBoard>()).clone(),
+            balls,
+            paddles,
+            lineup: world
+                .try_fetch::<Lineup>()
+                .map(|lineup| (*lineup).clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Put the saved state into the freshly initialized match in `world`.
+    pub fn restore(&self, world: &mut World) {
+        world.insert(self.clock.clone());
+        world.insert(self.scores.clone());
+        {
+            let mut balls = world.write_storage::<Ball>();
+            let mut transforms = world.write_storage::<Transform>();
+            for ((ball, transform), saved) in (&mut balls, &mut transforms).join().zip(&self.balls)
+            {
+                let [x, y, z] = saved.translation;
+                transform.set_translation_xyz(x, y, z);
+                transform.set_rotation_2d(saved.rotation);
+                let [vx, vy, vz] = saved.velocity;
+                ball.velocity = Vector3::new(vx, vy, vz);
+                ball.rot_velocity = saved.rot_velocity;
+                ball.hidden = saved.hidden;
+            }
+            let mut paddles = world.write_storage::<Paddle>();
+            for (paddle, transform) in (&mut paddles, &mut transforms).join() {
+                if let Some(saved) = self.paddles.iter().find(|saved| saved.side == paddle.side) {
+                    transform.set_translation_y(saved.y);
+                    paddle.velocity = saved.velocity;
+                }
+            }
+        }
+        if let Some(score_text) = world.try_fetch::<ScoreText>() {
+            let mut ui_text = world.write_storage::<UiText>();
+            if let Some(text) = ui_text.get_mut(score_text.p1_score) {
+                text.text = self.scores.score_left.to_string();
+            }
+            if let Some(text) = ui_text.get_mut(score_text.p2_score) {
+                text.text = self.scores.score_right.to_string();
+            }
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        load_ron(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_ron(self, path)
+    }
+}
@@ -0,0 +1,126 @@
+use amethyst::{
+    assets::Handle,
+    ecs::prelude::Entity,
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, WorldExt},
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use std::fs;
+
+use crate::{
+    find_ui,
+    locale::Locale,
+    paths::tournament_file,
+    states::{GameState, MainMenuState, State},
+    tournament::Tournament,
+};
+
+const MENU_LBL_BRACKET_ID: &str = "lbl_bracket";
+const MENU_BTN_PLAY_ID: &str = "btn_play";
+const MENU_BTN_MAIN_MENU_ID: &str = "btn_main_menu";
+const MENU_RON: &str = "ui/bracket.ron";
+
+/// Shows the bracket of a tournament between its matches.
+pub struct BracketState {
+    tournament: Tournament,
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    label: Option<Entity>,
+    play: Option<Entity>,
+    main_menu: Option<Entity>,
+}
+
+impl BracketState {
+    pub fn new(tournament: Tournament) -> Self {
+        BracketState {
+            tournament,
+            ui: None,
+            root: None,
+            label: None,
+            play: None,
+            main_menu: None,
+        }
+    }
+
+    fn overview(&self, locale: &Locale) -> String {
+        let overview = self.tournament.display(locale);
+        match self.tournament.next_players() {
+            Some((left, right)) => format!(
+                "{}\n\n{}",
+                overview,
+                locale.format("bracket.next", &[left, right])
+            ),
+            None => overview,
+        }
+    }
+}
+
+impl SimpleState for BracketState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::Bracket))
+            .build()
+            .into();
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        exec_removal(&world.entities(), &world.read_storage(), State::Bracket);
+        self.root = None;
+        self.label = None;
+        self.play = None;
+        self.main_menu = None;
+    }
+    fn handle_event(
+        &mut self,
+        _: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use StateEvent::*;
+        match event {
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                if Some(ui_event.target) == self.main_menu {
+                    // Progress is saved, the tournament can be continued later on.
+                    SimpleTrans::Switch(Box::from(MainMenuState::default()))
+                } else if Some(ui_event.target) == self.play {
+                    if self.tournament.next_match().is_some() {
+                        let tournament = self.tournament.clone();
+                        SimpleTrans::Switch(Box::from(GameState::with_tournament(tournament)))
+                    } else {
+                        // The tournament is over, nothing left to continue.
+                        if let Err(e) = fs::remove_file(tournament_file()) {
+                            eprintln!("Failed to remove the finished tournament: {}", e);
+                        }
+                        SimpleTrans::Switch(Box::from(MainMenuState::default()))
+                    }
+                } else {
+                    SimpleTrans::None
+                }
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.label.is_none() || self.play.is_none() || self.main_menu.is_none() {
+            self.label = data.world.exec(find_ui(MENU_LBL_BRACKET_ID));
+            self.play = data.world.exec(find_ui(MENU_BTN_PLAY_ID));
+            self.main_menu = data.world.exec(find_ui(MENU_BTN_MAIN_MENU_ID));
+            if let Some(label) = self.label {
+                if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                    text.text = self.overview(&data.world.read_resource());
+                }
+            }
+        }
+        SimpleTrans::None
+    }
+}
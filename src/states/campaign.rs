@@ -0,0 +1,165 @@
+use amethyst::{
+    assets::Handle,
+    ecs::prelude::Entity,
+    prelude::{
+        Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, World, WorldExt,
+    },
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use crate::{
+    campaign::{Campaign, CampaignProgress},
+    find_ui,
+    locale::Locale,
+    paths::campaign_file,
+    states::{GameState, MainMenuState, State},
+};
+
+const MENU_LBL_CAMPAIGN_ID: &str = "lbl_campaign";
+const MENU_BTN_PREV_ID: &str = "btn_prev";
+const MENU_BTN_NEXT_ID: &str = "btn_next";
+const MENU_BTN_PLAY_ID: &str = "btn_play";
+const MENU_BTN_BACK_ID: &str = "btn_back";
+const MENU_RON: &str = "ui/campaign.ron";
+
+/// Lists the campaign stages and lets the player choose an unlocked one.
+#[derive(Default)]
+pub struct CampaignState {
+    progress: CampaignProgress,
+    selected: usize,
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    label: Option<Entity>,
+    prev: Option<Entity>,
+    next: Option<Entity>,
+    play: Option<Entity>,
+    back: Option<Entity>,
+}
+
+impl CampaignState {
+    /// Select the next unlocked stage in the given direction, wrapping around.
+    fn select(&mut self, world: &World, forward: bool) {
+        let stages = world.read_resource::<Campaign>().stages.len();
+        let unlocked = (0..stages)
+            .filter(|&stage| self.progress.is_unlocked(stage))
+            .count();
+        if unlocked == 0 {
+            return;
+        }
+        self.selected = if forward {
+            (self.selected + 1) % unlocked
+        } else {
+            (self.selected + unlocked - 1) % unlocked
+        };
+    }
+
+    fn show_stages(&self, world: &World) {
+        let label = match self.label {
+            Some(label) => label,
+            None => return,
+        };
+        let campaign = world.read_resource::<Campaign>();
+        let locale = world.read_resource::<Locale>();
+        let mut overview = format!("{}\n", locale.get("campaign.title"));
+        for (i, stage) in campaign.stages.iter().enumerate() {
+            let marker = if i == self.selected { ">" } else { " " };
+            let status = if !self.progress.is_unlocked(i) {
+                format!("  {}", locale.get("campaign.locked"))
+            } else if self.progress.is_unlocked(i + 1) {
+                format!("  {}", locale.get("campaign.beaten"))
+            } else {
+                String::new()
+            };
+            overview += &format!("{} {}. {}{}\n", marker, i + 1, stage.name, status);
+        }
+        if let Some(text) = world.write_storage::<UiText>().get_mut(label) {
+            text.text = overview;
+        }
+    }
+}
+
+impl SimpleState for CampaignState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+        world.entry::<Campaign>().or_insert_with(Campaign::default);
+
+        // Continue with the latest unlocked stage
+        self.progress = CampaignProgress::load(campaign_file());
+        let stages = world.read_resource::<Campaign>().stages.len();
+        self.selected = (0..stages)
+            .filter(|&stage| self.progress.is_unlocked(stage))
+            .last()
+            .unwrap_or(0);
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::Campaign))
+            .build()
+            .into();
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        exec_removal(&world.entities(), &world.read_storage(), State::Campaign);
+        self.root = None;
+        self.label = None;
+        self.prev = None;
+        self.next = None;
+        self.play = None;
+        self.back = None;
+    }
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use StateEvent::*;
+        match event {
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                if Some(ui_event.target) == self.back {
+                    SimpleTrans::Switch(Box::from(MainMenuState::default()))
+                } else if Some(ui_event.target) == self.play {
+                    if data.world.read_resource::<Campaign>().stages.is_empty() {
+                        eprintln!("The campaign has no stages");
+                        return SimpleTrans::None;
+                    }
+                    SimpleTrans::Switch(Box::from(GameState::with_campaign_stage(self.selected)))
+                } else if Some(ui_event.target) == self.prev {
+                    self.select(data.world, false);
+                    self.show_stages(data.world);
+                    SimpleTrans::None
+                } else if Some(ui_event.target) == self.next {
+                    self.select(data.world, true);
+                    self.show_stages(data.world);
+                    SimpleTrans::None
+                } else {
+                    SimpleTrans::None
+                }
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.label.is_none()
+            || self.prev.is_none()
+            || self.next.is_none()
+            || self.play.is_none()
+            || self.back.is_none()
+        {
+            self.label = data.world.exec(find_ui(MENU_LBL_CAMPAIGN_ID));
+            self.prev = data.world.exec(find_ui(MENU_BTN_PREV_ID));
+            self.next = data.world.exec(find_ui(MENU_BTN_NEXT_ID));
+            self.play = data.world.exec(find_ui(MENU_BTN_PLAY_ID));
+            self.back = data.world.exec(find_ui(MENU_BTN_BACK_ID));
+            self.show_stages(data.world);
+        }
+        SimpleTrans::None
+    }
+}
@@ -0,0 +1,148 @@
+use amethyst::{
+    assets::Handle,
+    ecs::prelude::Entity,
+    input::InputEvent,
+    prelude::{
+        Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, TransEvent, World,
+        WorldExt,
+    },
+    shrev::EventChannel,
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use super::menu_focus::MenuFocus;
+use crate::{find_ui, locale::Locale, menu::MenuConfig, states::State};
+
+const MENU_LBL_QUESTION_ID: &str = "lbl_question";
+const MENU_BTN_YES_ID: &str = "btn_yes";
+const MENU_BTN_NO_ID: &str = "btn_no";
+const MENU_RON: &str = "ui/confirm.ron";
+const ACTION_PAUSE: &str = "pause";
+
+/// Asks a yes or no question on top of another menu.
+/// Answered with yes, it pops itself and carries out the transitions it was given, one after the other.
+pub struct ConfirmState {
+    /// Key of the question in the string tables.
+    question: String,
+    then: Vec<TransEvent<GameData<'static, 'static>, StateEvent>>,
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    label: Option<Entity>,
+    yes: Option<Entity>,
+    no: Option<Entity>,
+    focus: MenuFocus,
+}
+
+impl ConfirmState {
+    pub fn new(
+        question: &str,
+        then: Vec<TransEvent<GameData<'static, 'static>, StateEvent>>,
+    ) -> Self {
+        ConfirmState {
+            question: question.to_string(),
+            then,
+            ui: None,
+            root: None,
+            label: None,
+            yes: None,
+            no: None,
+            focus: MenuFocus::default(),
+        }
+    }
+}
+
+/// Carry out the transitions one after the other, after asking the `question` key unless
+/// `MenuConfig::confirm` is off.
+pub fn confirm(
+    world: &World,
+    question: &str,
+    then: Vec<TransEvent<GameData<'static, 'static>, StateEvent>>,
+) -> SimpleTrans {
+    let ask = world
+        .try_fetch::<MenuConfig>()
+        .map(|config| config.confirm)
+        .unwrap_or(true);
+    if ask {
+        SimpleTrans::Push(Box::from(ConfirmState::new(question, then)))
+    } else {
+        world
+            .write_resource::<EventChannel<TransEvent<GameData<'_, '_>, StateEvent>>>()
+            .iter_write(then);
+        SimpleTrans::None
+    }
+}
+
+impl SimpleState for ConfirmState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::Confirm))
+            .build()
+            .into();
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        exec_removal(&world.entities(), &world.read_storage(), State::Confirm);
+        self.root = None;
+        self.label = None;
+        self.yes = None;
+        self.no = None;
+        self.focus = MenuFocus::default();
+    }
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use InputEvent::*;
+        use StateEvent::*;
+        if self.focus.handle_event(data.world, &event) {
+            return SimpleTrans::Pop;
+        }
+        match event {
+            // Pausing again from the dialog answers no
+            Input(ActionPressed(action)) if action == ACTION_PAUSE => SimpleTrans::Pop,
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                if Some(ui_event.target) == self.yes {
+                    data.world
+                        .write_resource::<EventChannel<TransEvent<GameData<'_, '_>, StateEvent>>>()
+                        .iter_write(self.then.drain(..));
+                    SimpleTrans::Pop
+                } else if Some(ui_event.target) == self.no {
+                    SimpleTrans::Pop
+                } else {
+                    SimpleTrans::None
+                }
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.label.is_none() || self.yes.is_none() || self.no.is_none() {
+            self.label = data.world.exec(find_ui(MENU_LBL_QUESTION_ID));
+            self.yes = data.world.exec(find_ui(MENU_BTN_YES_ID));
+            self.no = data.world.exec(find_ui(MENU_BTN_NO_ID));
+            self.focus = MenuFocus::new([self.yes, self.no].iter().flatten().copied().collect());
+            if let Some(label) = self.label {
+                if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                    text.text = data
+                        .world
+                        .read_resource::<Locale>()
+                        .get(&self.question)
+                        .to_string();
+                }
+            }
+        }
+        SimpleTrans::None
+    }
+}
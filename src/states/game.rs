@@ -1,43 +1,317 @@
 use amethyst::{
     assets::{AssetStorage, Handle, Loader},
-    core::{math::Vector3, transform::Transform, ArcThreadPool, SystemExt},
-    ecs::{world::Builder, Dispatcher, DispatcherBuilder},
+    core::{math::Vector3, timing::Time, transform::Transform, ArcThreadPool, SystemExt},
+    ecs::{prelude::Entity, world::Builder, Dispatcher, DispatcherBuilder, Join},
+    input::{Axis, InputHandler, StringBindings},
     prelude::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, World, WorldExt},
     renderer::{
-        transparent::Transparent, Camera, ImageFormat, SpriteRender, SpriteSheet,
-        SpriteSheetFormat, Texture,
+        palette::Srgba, resources::Tint, transparent::Transparent, Camera, ImageFormat,
+        SpriteRender, SpriteSheet, SpriteSheetFormat, Texture,
     },
     ui::{Anchor, TtfFormat, UiText, UiTransform},
     utils::removal::{exec_removal, Removal},
 };
 
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use std::path::PathBuf;
+
 use crate::{
+    accessibility::{set_game_speed, AccessibilityConfig},
+    achievements::{initialize_toast, AchievementProgress},
+    audio::{initialize_audio, Sounds},
+    campaign::{Campaign, CampaignProgress, Stage},
+    effects::{initialize_effects, CameraShake},
+    hud::{initialize_court_hud, initialize_spin_hud},
+    locale::Locale,
+    paths::{achievements_file, campaign_file, profiles_file, tournament_file},
     pong::{
-        pause_requested, random_45_vec, Ai, Ball, Paddle, PausedOrRunning, ScoreBoard, ScoreText,
-        Side, ARENA_HEIGHT, ARENA_WIDTH, BALL_RADIUS, BALL_RADIUS_COLLISION, BALL_TEXTURE_SIZE,
-        INITIAL_BALL_SPEED, PADDLE_SIZE, PADDLE_SIZE_COLLISION, PADDLE_TEXTURE_SIZE,
-        PADDLE_WALL_OFFSET,
+        pause_requested, random_45_vec, Ai, AiParams, ArenaCamera, Ball, Difficulty, GameRng,
+        Paddle, PausedOrRunning, PlayerNames, ScaledUi, ScoreBoard, ScoreText, Side, ARENA_HEIGHT,
+        ARENA_WIDTH, BALL_RADIUS, BALL_RADIUS_COLLISION, BALL_TEXTURE_SIZE, INITIAL_BALL_SPEED,
+        PADDLE_SIZE, PADDLE_SIZE_COLLISION, PADDLE_TEXTURE_SIZE, PADDLE_WALL_OFFSET,
     },
-    states::{PauseState, State},
+    profile::{Lineup, Profiles},
+    rating::rating_change,
+    replay::{Frame, PlaySettings, Replay},
+    rules::{MatchClock, MatchPhase, MatchRules},
+    save::SavedMatch,
+    states::{GameOverState, MainMenuState, Next, PauseState, State},
     systems,
+    theme::Theme,
+    tournament::Tournament,
 };
 
+/// Length of a tournament match, if the configured rules would play an endless match.
+const TOURNAMENT_MATCH_DURATION: f32 = 180.0;
+
+/// The kind of match that is played.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Mode {
+    SinglePlayer,
+    TwoPlayers,
+    /// The next match of the tournament.
+    Tournament(Tournament),
+    /// The given stage of the campaign.
+    Campaign(usize),
+}
+
+/// What a match does with a replay.
+enum Replaying {
+    /// Record the match once it's set up, with the serves seeded by `seed`.
+    Start { path: PathBuf, seed: u64 },
+    /// Record the match, to save it to the file when it's over.
+    Record { path: PathBuf, replay: Replay },
+    /// Play back the replay, continuing at the frame with the index `next`.
+    /// The replay's settings replace the `configured` ones until it stops.
+    Play {
+        replay: Replay,
+        next: usize,
+        configured: Option<PlaySettings>,
+    },
+}
+
 pub struct GameState<'a, 'b> {
     dispatcher: Option<Dispatcher<'a, 'b>>,
-    two_players: bool,
+    mode: Mode,
+    /// A saved match to continue, instead of starting a fresh one.
+    saved: Option<SavedMatch>,
+    /// The profiles playing the match.
+    lineup: Lineup,
+    /// Paddle axes replaced by the controls of a profile, with the configured binding to restore.
+    replaced_axes: Vec<(String, Option<Axis>)>,
+    replay: Option<Replaying>,
 }
 
 impl GameState<'_, '_> {
     pub fn with_single_player() -> Self {
+        GameState::new(Mode::SinglePlayer)
+    }
+    pub fn with_two_players() -> Self {
+        GameState::new(Mode::TwoPlayers)
+    }
+    /// Play the next match of the given tournament.
+    pub fn with_tournament(tournament: Tournament) -> Self {
+        GameState::new(Mode::Tournament(tournament))
+    }
+    /// Play against the opponent of the given campaign stage.
+    pub fn with_campaign_stage(stage: usize) -> Self {
+        GameState::new(Mode::Campaign(stage))
+    }
+    /// Continue a saved match where it stopped.
+    pub fn from_save(saved: SavedMatch) -> Self {
         GameState {
             dispatcher: None,
-            two_players: false,
+            mode: saved.mode.clone(),
+            lineup: saved.lineup.clone(),
+            saved: Some(saved),
+            replaced_axes: Vec::new(),
+            replay: None,
         }
     }
-    pub fn with_two_players() -> Self {
+    /// Play back a recorded match.
+    pub fn from_replay(replay: Replay) -> Self {
+        let mode = replay.mode.clone();
+        GameState {
+            replay: Some(Replaying::Play {
+                replay,
+                next: 0,
+                configured: None,
+            }),
+            ..GameState::new(mode)
+        }
+    }
+    fn new(mode: Mode) -> Self {
         GameState {
             dispatcher: None,
-            two_players: true,
+            mode,
+            saved: None,
+            lineup: Lineup::default(),
+            replaced_axes: Vec::new(),
+            replay: None,
+        }
+    }
+    /// Record the match and save the replay to `path`.
+    /// The serves have to come from a `GameRng` seeded with `seed`.
+    pub fn with_recording(mut self, path: PathBuf, seed: u64) -> Self {
+        self.replay = Some(Replaying::Start { path, seed });
+        self
+    }
+    /// Play as the given profiles.
+    pub fn with_lineup(mut self, lineup: Lineup) -> Self {
+        self.lineup = lineup;
+        self
+    }
+
+    /// The campaign stage that is played, if any.
+    fn stage(&self, world: &World) -> Option<Stage> {
+        match self.mode {
+            Mode::Campaign(stage) => world
+                .try_fetch::<Campaign>()
+                .and_then(|campaign| campaign.stages.get(stage).cloned()),
+            _ => None,
+        }
+    }
+
+    /// Name of the player on the given side.
+    fn player_name(&self, world: &World, side: Side) -> String {
+        let locale = world.read_resource::<Locale>();
+        let names = match &self.mode {
+            Mode::Tournament(tournament) => tournament
+                .next_players()
+                .map(|(left, right)| (left.to_string(), right.to_string())),
+            Mode::Campaign(_) => self
+                .stage(world)
+                .map(|stage| (locale.get("player.you").to_string(), stage.name)),
+            _ => None,
+        };
+        match (names, side) {
+            (Some((left, _)), Side::Left) => left,
+            (Some((_, right)), Side::Right) => right,
+            (None, side) => match self.lineup.name(side) {
+                Some(name) => name.to_string(),
+                None if side == Side::Left => locale.get("player.left").to_string(),
+                None => locale.get("player.right").to_string(),
+            },
+        }
+    }
+
+    /// Tint the paddles and bind the controls of the profiles in the lineup.
+    fn apply_profiles(&mut self, world: &mut World) {
+        let profiles = Profiles::load(profiles_file());
+        let lineup = &self.lineup;
+        let profile = |side| lineup.name(side).and_then(|name| profiles.get(name));
+
+        let paddles = (&world.entities(), &world.read_storage::<Paddle>())
+            .join()
+            .map(|(entity, paddle)| (entity, paddle.side))
+            .collect::<Vec<_>>();
+        // The colour-blind palette goes before the colours of the profiles
+        let palette = world.read_resource::<AccessibilityConfig>().palette;
+        for (entity, side) in paddles {
+            if palette.side(side).is_some() {
+                continue;
+            }
+            if let Some([red, green, blue, alpha]) = profile(side).and_then(|p| p.paddle_color) {
+                world
+                    .write_storage::<Tint>()
+                    .insert(entity, Tint(Srgba::new(red, green, blue, alpha)))
+                    .expect("Paddle is alive");
+            }
+        }
+
+        let mut input = world.write_resource::<InputHandler<StringBindings>>();
+        for &(side, axis_name) in &[(Side::Left, "left_paddle"), (Side::Right, "right_paddle")] {
+            let controls = match profile(side).and_then(|p| p.controls.clone()) {
+                Some(controls) => controls,
+                None => continue,
+            };
+            match input.bindings.insert_axis(axis_name, controls) {
+                Ok(configured) => self.replaced_axes.push((axis_name.to_string(), configured)),
+                Err(e) => eprintln!(
+                    "Failed to bind the controls of {}: {}",
+                    self.player_name(world, side),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Give the paddle axes their configured bindings back.
+    fn restore_controls(&mut self, world: &mut World) {
+        let mut input = world.write_resource::<InputHandler<StringBindings>>();
+        for (axis_name, configured) in self.replaced_axes.drain(..) {
+            match configured {
+                Some(axis) => {
+                    if let Err(e) = input.bindings.insert_axis(axis_name, axis) {
+                        eprintln!("Failed to restore the configured controls: {}", e);
+                    }
+                }
+                None => {
+                    input.bindings.remove_axis(&axis_name);
+                }
+            }
+        }
+    }
+
+    /// Add the finished match to the statistics of the profiles in the lineup and rate them
+    /// against each other, or against the AI in a single player match.
+    /// Returns the new ratings and their changes, for the game over screen.
+    fn record_profiles(&self, world: &World, winner: Side) -> String {
+        if self.lineup == Lineup::default() {
+            return String::new();
+        }
+        let mut profiles = Profiles::load(profiles_file());
+        let rating = |profiles: &Profiles, side| match self.lineup.name(side) {
+            Some(name) => profiles.get(name).map(|profile| profile.rating),
+            None => match self.mode {
+                Mode::SinglePlayer if side == Side::Right => Some(
+                    world
+                        .try_fetch::<Difficulty>()
+                        .map(|difficulty| difficulty.rating())
+                        .unwrap_or_else(|| Difficulty::default().rating()),
+                ),
+                _ => None,
+            },
+        };
+        let ratings = [
+            rating(&profiles, Side::Left),
+            rating(&profiles, Side::Right),
+        ];
+
+        let scores = world.read_resource::<ScoreBoard>();
+        let mut changes = Vec::new();
+        for &(side, own, other) in &[
+            (Side::Left, ratings[0], ratings[1]),
+            (Side::Right, ratings[1], ratings[0]),
+        ] {
+            let profile = match self
+                .lineup
+                .name(side)
+                .and_then(|name| profiles.get_mut(name))
+            {
+                Some(profile) => profile,
+                None => continue,
+            };
+            profile.stats.record(side, &scores, winner);
+            // Only matches against a rated opponent count for the rating
+            if let (Some(own), Some(other)) = (own, other) {
+                let change = rating_change(own, other, side, winner);
+                profile.rating += change;
+                changes.push(format!(
+                    "{} {:.0} ({:+.0})",
+                    profile.name, profile.rating, change
+                ));
+            }
+        }
+        if let Err(e) = profiles.save(profiles_file()) {
+            eprintln!("Failed to save the profiles: {}", e);
+        }
+        changes.join("   ")
+    }
+
+    /// Record the result of the finished match and decide where to go next.
+    fn finish(&mut self, world: &World, winner: Side) -> Next {
+        match &mut self.mode {
+            Mode::Tournament(tournament) => {
+                tournament.record(&world.read_resource::<ScoreBoard>());
+                if let Err(e) = tournament.save(tournament_file()) {
+                    eprintln!("Failed to save the tournament: {}", e);
+                }
+                Next::Bracket(tournament.clone())
+            }
+            Mode::Campaign(stage) => {
+                if winner == Side::Left {
+                    let mut progress = CampaignProgress::load(campaign_file());
+                    progress.beat(*stage);
+                    if let Err(e) = progress.save(campaign_file()) {
+                        eprintln!("Failed to save the campaign progress: {}", e);
+                    }
+                }
+                Next::Campaign
+            }
+            _ => Next::MainMenu,
         }
     }
 }
@@ -46,165 +320,438 @@ impl SimpleState for GameState<'_, '_> {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
         world.register::<Removal<State>>();
+        // The main menu is skipped, when starting from the command line
+        if !world.has_value::<Sounds>() {
+            initialize_audio(world);
+        }
         // Create a blank score board
         world.insert(ScoreBoard::default());
+        // Loaded here rather than by the system, so headless matches leave the file alone
+        world.insert(AchievementProgress::load(achievements_file()));
+        // A replay is set up like the match it was recorded from
+        if let Some(Replaying::Play {
+            replay, configured, ..
+        }) = &mut self.replay
+        {
+            world.insert(GameRng::from_seed(replay.seed));
+            world.insert(replay.difficulty);
+            world.insert(replay.difficulty.ai_params());
+            *configured = Some(replay.settings.apply(world));
+        }
+        // Start the clock with the configured rules,
+        // campaign stages and replays bring their own
+        let stage = self.stage(world);
+        let mut rules = match (&stage, &self.replay) {
+            (_, Some(Replaying::Play { replay, .. })) => replay.rules.clone(),
+            (Some(stage), _) => stage.rules.clone(),
+            (None, _) => world
+                .try_fetch::<MatchRules>()
+                .map(|rules| (*rules).clone())
+                .unwrap_or_default(),
+        };
+        if let Mode::Tournament(_) = self.mode {
+            if rules.duration.is_none() {
+                // Tournament matches need to come to an end
+                rules.duration = Some(TOURNAMENT_MATCH_DURATION);
+            }
+        }
+        let arena = stage
+            .as_ref()
+            .map(|stage| stage.arena.clone())
+            .unwrap_or_default();
+        if let Some(Replaying::Start { path, seed }) = self.replay.take() {
+            let difficulty = world
+                .try_fetch::<Difficulty>()
+                .map(|difficulty| *difficulty)
+                .unwrap_or_default();
+            let settings = PlaySettings::current(world);
+            let replay = Replay::new(self.mode.clone(), seed, difficulty, rules.clone(), settings);
+            self.replay = Some(Replaying::Record { path, replay });
+        }
+        let mut clock = MatchClock::new(&rules);
+        clock.ball_speed_factor = arena.ball_speed_factor;
+        world.insert(clock);
 
         // Create the `DispatcherBuilder` and register some `System`s
         // that should only run for this `State`.
-        let mut dispatcher = DispatcherBuilder::new()
-            .with(
-                systems::PaddleSystem.pausable(PausedOrRunning::Running),
-                "paddle_system",
-                &[], //&["input_system"],
-            )
-            .with(
-                systems::MoveBallsSystem.pausable(PausedOrRunning::Running),
-                "ball_system",
-                &[],
-            )
-            .with(
-                systems::BounceSystem.pausable(PausedOrRunning::Running),
-                "collision_system",
-                &["paddle_system", "ball_system"],
-            )
-            .with(
-                systems::WinnerSystem.pausable(PausedOrRunning::Running),
-                "winner_system",
-                &["ball_system"],
-            )
-            .with(
-                systems::AiSystem.pausable(PausedOrRunning::Running),
-                "ai_system",
-                &["paddle_system", "ball_system"],
-            )
+        let mut dispatcher = game_dispatcher(world)
             .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
             .build();
         dispatcher.setup(world);
         self.dispatcher = Some(dispatcher);
 
+        // Add AI if only one player is playing
+        let ai = match self.mode {
+            Mode::SinglePlayer => Some(Ai {
+                params: world
+                    .try_fetch::<AiParams>()
+                    .map(|params| (*params).clone())
+                    .unwrap_or_default(),
+            }),
+            Mode::Campaign(_) => Some(Ai {
+                params: stage.map(|stage| stage.ai).unwrap_or_default(),
+            }),
+            Mode::TwoPlayers | Mode::Tournament(_) => None,
+        };
+
+        let names = PlayerNames {
+            left: self.player_name(world, Side::Left),
+            right: self.player_name(world, Side::Right),
+        };
+        world.insert(names);
+        world.insert(self.lineup.clone());
+
         let sprites = load_sprite_sheet(world);
         initialize_scoreboard(world);
-        initialize_camera(world);
-        initialize_ball(world, sprites.clone());
-        initialize_paddles(world, sprites.clone(), self.two_players);
+        initialize_court_hud(world);
+        initialize_toast(world);
+        initialize_camera(world, State::Game);
+        initialize_center_line(world, sprites.clone(), State::Game);
+        initialize_letterbox(world, sprites.clone(), State::Game);
+        initialize_ball(
+            world,
+            sprites.clone(),
+            INITIAL_BALL_SPEED * arena.ball_speed_factor,
+            State::Game,
+        );
+        initialize_paddles(world, sprites.clone(), ai);
+        self.apply_profiles(world);
+        initialize_effects(world, sprites.clone(), State::Game);
+        initialize_spin_hud(world, sprites, State::Game, false);
+
+        if let Some(saved) = self.saved.take() {
+            saved.restore(world);
+        }
+        set_game_speed(world, true);
+    }
+    // The menus on top of the match run at normal speed
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        set_game_speed(data.world, false);
+    }
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        set_game_speed(data.world, true);
     }
     fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
+        set_game_speed(world, false);
         exec_removal(&world.entities(), &world.read_storage(), State::Game);
+        self.restore_controls(world);
+        match self.replay.take() {
+            Some(Replaying::Record { path, replay }) => {
+                if let Err(e) = replay.save(&path) {
+                    eprintln!("Failed to save the replay to {}: {}", path.display(), e);
+                }
+            }
+            Some(Replaying::Play {
+                configured: Some(configured),
+                ..
+            }) => {
+                configured.apply(world);
+            }
+            _ => (),
+        }
+        if let Some(progress) = world.remove::<AchievementProgress>() {
+            if let Err(e) = progress.save(achievements_file()) {
+                eprintln!("Failed to save the achievements: {}", e);
+            }
+        }
+        world.remove::<Frame>();
     }
     fn handle_event(
         &mut self,
-        _: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        if pause_requested(&event) {
-            return SimpleTrans::Push(Box::from(PauseState::default()));
+        if pause_requested(&event, &data.world.read_resource()) {
+            return SimpleTrans::Push(Box::from(PauseState::with_save(self.mode.clone())));
         }
 
         SimpleTrans::None
     }
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        match &mut self.replay {
+            Some(Replaying::Record { replay, .. }) => {
+                let input = data.world.read_resource::<InputHandler<StringBindings>>();
+                replay.frames.push(Frame {
+                    delta: data.world.read_resource::<Time>().delta_seconds(),
+                    left_paddle: input.axis_value("left_paddle"),
+                    right_paddle: input.axis_value("right_paddle"),
+                });
+            }
+            Some(Replaying::Play { replay, next, .. }) => match replay.frames.get(*next) {
+                Some(&frame) => {
+                    *next += 1;
+                    {
+                        // The recorded time is scaled already
+                        let mut time = data.world.write_resource::<Time>();
+                        let scale = time.time_scale();
+                        time.set_delta_seconds(frame.delta / scale);
+                    }
+                    data.world.insert(frame);
+                }
+                // The recording stopped before the match was over
+                None => return SimpleTrans::Switch(Box::from(MainMenuState::default())),
+            },
+            _ => (),
+        }
         if let Some(dispatcher) = self.dispatcher.as_mut() {
             dispatcher.dispatch(&data.world);
         }
 
+        let phase = data.world.read_resource::<MatchClock>().phase;
+        if let MatchPhase::Finished(winner) = phase {
+            let message = data
+                .world
+                .read_resource::<Locale>()
+                .format("game_over.wins", &[&self.player_name(&data.world, winner)]);
+            let ratings = self.record_profiles(data.world, winner);
+            let next = self.finish(&data.world, winner);
+            return SimpleTrans::Push(Box::from(
+                GameOverState::new(message, next).with_ratings(ratings),
+            ));
+        }
+
         SimpleTrans::None
     }
 }
 
+/// The systems that play a match.
+/// Shared with headless matches, which run without a window.
+pub fn match_dispatcher<'a, 'b>() -> DispatcherBuilder<'a, 'b> {
+    DispatcherBuilder::new()
+        .with(
+            systems::PaddleSystem.pausable(PausedOrRunning::Running),
+            "paddle_system",
+            &[], //&["input_system"],
+        )
+        .with(
+            systems::MoveBallsSystem.pausable(PausedOrRunning::Running),
+            "ball_system",
+            &[],
+        )
+        .with(
+            systems::BounceSystem.pausable(PausedOrRunning::Running),
+            "collision_system",
+            &["paddle_system", "ball_system"],
+        )
+        .with(
+            systems::WinnerSystem.pausable(PausedOrRunning::Running),
+            "winner_system",
+            &["ball_system"],
+        )
+        .with(
+            systems::AiSystem.pausable(PausedOrRunning::Running),
+            "ai_system",
+            &["paddle_system", "ball_system"],
+        )
+        .with(
+            systems::ClockSystem.pausable(PausedOrRunning::Running),
+            "clock_system",
+            &["winner_system"],
+        )
+}
+
+/// The systems of a match played in `GameState`: the match itself, its effects and its HUD.
+/// Shared with headless matches, so tests run what the players see.
+pub fn game_dispatcher<'a, 'b>(world: &mut World) -> DispatcherBuilder<'a, 'b> {
+    let effects = systems::EffectsSystem::new(world);
+    let juice = systems::JuiceSystem::new(world);
+    let court_hud = systems::CourtHudSystem::new(world);
+    let achievements = systems::AchievementSystem::new(world);
+    match_dispatcher()
+        .with(
+            effects.pausable(PausedOrRunning::Running),
+            "effects_system",
+            &["collision_system", "winner_system"],
+        )
+        .with(
+            juice,
+            "juice_system",
+            &["collision_system", "winner_system"],
+        )
+        .with(
+            systems::SpinHudSystem.pausable(PausedOrRunning::Running),
+            "spin_hud_system",
+            &["collision_system", "winner_system"],
+        )
+        .with(
+            court_hud.pausable(PausedOrRunning::Running),
+            "court_hud_system",
+            &["winner_system"],
+        )
+        .with(
+            achievements.pausable(PausedOrRunning::Running),
+            "achievement_system",
+            &["collision_system", "winner_system", "clock_system"],
+        )
+}
+
 /// Initializes one paddle on the left, and one paddle on the right.
-fn initialize_paddles(world: &mut World, sprite_sheet: Handle<SpriteSheet>, two_players: bool) {
-    let mut left_transform = Transform::default();
-    let mut right_transform = Transform::default();
+/// The right paddle is controlled by the given `ai`, if any.
+fn initialize_paddles(world: &mut World, sprite_sheet: Handle<SpriteSheet>, ai: Option<Ai>) {
+    initialize_paddle(world, sprite_sheet.clone(), Side::Left, None, State::Game);
+    initialize_paddle(world, sprite_sheet, Side::Right, ai, State::Game);
+}
 
-    // Correctly position the paddles.
-    let y = ARENA_HEIGHT / 2.0;
-    left_transform.set_translation_xyz(PADDLE_WALL_OFFSET + PADDLE_SIZE_COLLISION[0] * 0.5, y, 0.0);
-    left_transform.set_scale(
-        [
-            PADDLE_SIZE[0] / PADDLE_TEXTURE_SIZE[0],
-            PADDLE_SIZE[1] / PADDLE_TEXTURE_SIZE[1],
-            1.0,
-        ]
-        .into(),
-    );
-    right_transform.set_translation_xyz(
-        ARENA_WIDTH - PADDLE_WALL_OFFSET - PADDLE_SIZE_COLLISION[0] * 0.5,
-        y,
-        0.0,
-    );
-    right_transform.set_scale(
-        [
-            PADDLE_SIZE[0] / PADDLE_TEXTURE_SIZE[0],
-            PADDLE_SIZE[1] / PADDLE_TEXTURE_SIZE[1],
-            1.0,
-        ]
-        .into(),
-    );
+/// Initializes a paddle on the given side, that is removed with `state`.
+pub fn initialize_paddle(
+    world: &mut World,
+    sprite_sheet: Handle<SpriteSheet>,
+    side: Side,
+    ai: Option<Ai>,
+    state: State,
+) -> Entity {
+    let transform = paddle_transform(side);
 
     // Assign the sprites for the paddles
+    let sprite_number = world
+        .entry::<Theme>()
+        .or_insert_with(Theme::default)
+        .sprites
+        .paddle(side);
     let sprite_render = SpriteRender {
-        sprite_sheet: sprite_sheet.clone(),
-        sprite_number: 0, // paddle is the first sprite in the sprite_sheet
+        sprite_sheet,
+        sprite_number,
     };
+    world.register::<Tint>();
+    let color = world
+        .entry::<AccessibilityConfig>()
+        .or_insert_with(AccessibilityConfig::default)
+        .palette
+        .side(side);
 
-    // Create a left plank entity.
-    world
+    // Create the plank entity.
+    let mut paddle = world
         .create_entity()
-        .with(Paddle::new(Side::Left))
-        .with(sprite_render.clone())
-        .with(left_transform)
-        .with(Transparent)
-        .with(Removal::new(State::Game))
-        .build();
-
-    // Create right plank entity.
-    let mut right = world
-        .create_entity()
-        .with(Paddle::new(Side::Right))
-        .with(sprite_render.clone())
-        .with(right_transform)
+        .with(Paddle::new(side))
+        .with(sprite_render)
+        .with(transform)
         .with(Transparent)
-        .with(Removal::new(State::Game));
-    // Add AI if only one player is playing
-    if !two_players {
-        right = right.with(Ai);
+        .with(Removal::new(state));
+    if let Some(ai) = ai {
+        paddle = paddle.with(ai);
+    }
+    if let Some([red, green, blue, alpha]) = color {
+        paddle = paddle.with(Tint(Srgba::new(red, green, blue, alpha)));
     }
-    right.build();
+    paddle.build()
 }
 
-fn initialize_camera(world: &mut World) {
+pub fn initialize_camera(world: &mut World, state: State) {
     // Setup camera in a way that our screen covers whole arena
     // and (0, 0) is in the bottom left.
+    // `LetterboxSystem` fits it to the window from the next frame on.
     let mut transform = Transform::default();
     transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 1.0);
 
     world
         .create_entity()
         .with(Camera::standard_2d(ARENA_WIDTH, ARENA_HEIGHT))
+        .with(ArenaCamera)
+        .with(CameraShake::new(*transform.translation()))
         .with(transform)
-        .with(Removal::new(State::Game))
+        .with(Removal::new(state))
         .build();
 }
 
-fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
+/// Initialises the dashes of the theme's centre line, if it has one.
+pub fn initialize_center_line(world: &mut World, sprite_sheet: Handle<SpriteSheet>, state: State) {
+    world.register::<Tint>();
+    let line = match world
+        .entry::<Theme>()
+        .or_insert_with(Theme::default)
+        .center_line
+        .clone()
+    {
+        Some(line) => line,
+        None => return,
+    };
+
+    let [red, green, blue, alpha] = line.tint;
+    for dash in 0..line.dashes {
+        let mut transform = Transform::default();
+        let y = ARENA_HEIGHT * (dash as f32 + 0.5) / line.dashes as f32;
+        transform.set_translation_xyz(ARENA_WIDTH / 2.0, y, -0.5);
+        transform.set_scale(Vector3::new(line.scale[0], line.scale[1], 1.0));
+        transform.set_rotation_2d(line.rotation.to_radians());
+        world
+            .create_entity()
+            .with(SpriteRender {
+                sprite_sheet: sprite_sheet.clone(),
+                sprite_number: line.sprite,
+            })
+            .with(transform)
+            .with(Tint(Srgba::new(red, green, blue, alpha)))
+            .with(Transparent)
+            .with(Removal::new(state))
+            .build();
+    }
+}
+
+/// Initialises the bars covering everything outside the arena, in front of the gameplay.
+/// They reach far enough for any window and the camera shake.
+pub fn initialize_letterbox(world: &mut World, sprite_sheet: Handle<SpriteSheet>, state: State) {
+    world.register::<Tint>();
+    let (sprite_number, [red, green, blue, alpha]) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (theme.sprites.solid, theme.letterbox)
+    };
+    // The solid sprite is as big as the ball texture
+    let reach = 10.0 * ARENA_WIDTH.max(ARENA_HEIGHT);
+    let bars = [
+        // left and right: (center, size)
+        ([-reach / 2.0, ARENA_HEIGHT / 2.0], [reach, 3.0 * reach]),
+        (
+            [ARENA_WIDTH + reach / 2.0, ARENA_HEIGHT / 2.0],
+            [reach, 3.0 * reach],
+        ),
+        // bottom and top
+        ([ARENA_WIDTH / 2.0, -reach / 2.0], [ARENA_WIDTH, reach]),
+        (
+            [ARENA_WIDTH / 2.0, ARENA_HEIGHT + reach / 2.0],
+            [ARENA_WIDTH, reach],
+        ),
+    ];
+    for ([x, y], [width, height]) in bars.iter() {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(*x, *y, 0.5);
+        transform.set_scale(Vector3::new(
+            width / BALL_TEXTURE_SIZE[0],
+            height / BALL_TEXTURE_SIZE[1],
+            1.0,
+        ));
+        world
+            .create_entity()
+            .with(SpriteRender {
+                sprite_sheet: sprite_sheet.clone(),
+                sprite_number,
+            })
+            .with(transform)
+            .with(Tint(Srgba::new(red, green, blue, alpha)))
+            .with(Transparent)
+            .with(Removal::new(state))
+            .build();
+    }
+}
+
+/// Loads the sprite sheet of the theme.
+pub fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
+    let (texture, sprite_sheet) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (theme.texture.clone(), theme.sprite_sheet.clone())
+    };
     // Load the sprite sheet necessary to render the graphics.
     // The texture is the pixel data
     // `texture_handle` is a cloneable reference to the texture
     let texture_handle = {
         let loader = world.read_resource::<Loader>();
         let texture_storage = world.read_resource::<AssetStorage<Texture>>();
-        loader.load(
-            "texture/sprites.png",
-            ImageFormat::default(),
-            (),
-            &texture_storage,
-        )
+        loader.load(texture, ImageFormat::default(), (), &texture_storage)
     };
     let loader = world.read_resource::<Loader>();
     let sprite_sheet_store = world.read_resource::<AssetStorage<SpriteSheet>>();
     loader.load(
-        "texture/sprites.ron", // Here we load the associated ron file
+        sprite_sheet, // Here we load the associated ron file
         SpriteSheetFormat(texture_handle),
         (),
         &sprite_sheet_store,
@@ -212,54 +759,122 @@ fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
 }
 
 /// Initialises one ball in the middle-ish of the arena.
-fn initialize_ball(world: &mut World, sprite_sheet_handle: Handle<SpriteSheet>) {
-    // Create the translation.
-    let mut local_transform = Transform::default();
-    local_transform.set_translation_xyz(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0, 0.0);
-    local_transform.set_scale(
-        [
-            2.0 * BALL_RADIUS / BALL_TEXTURE_SIZE[0],
-            2.0 * BALL_RADIUS / BALL_TEXTURE_SIZE[1],
-            1.0,
-        ]
-        .into(),
-    );
+pub fn initialize_ball(
+    world: &mut World,
+    sprite_sheet_handle: Handle<SpriteSheet>,
+    speed: f32,
+    state: State,
+) -> Entity {
+    let (scale, color) = {
+        let config = world
+            .entry::<AccessibilityConfig>()
+            .or_insert_with(AccessibilityConfig::default);
+        (config.ball_scale, config.palette.ball())
+    };
+    let mut local_transform = ball_transform();
+    local_transform.set_scale(local_transform.scale() * scale);
 
     // Assign the sprite for the ball
+    let sprite_number = world
+        .entry::<Theme>()
+        .or_insert_with(Theme::default)
+        .sprites
+        .ball;
     let sprite_render = SpriteRender {
         sprite_sheet: sprite_sheet_handle,
-        sprite_number: 1, // ball is the second sprite on the sprite sheet
+        sprite_number,
     };
 
-    world
+    let ball = {
+        let mut rng = world.entry::<GameRng>().or_insert_with(GameRng::default);
+        let ball = serve(&mut rng.0, speed);
+        Ball {
+            radius: ball.radius * scale,
+            ..ball
+        }
+    };
+
+    world.register::<Tint>();
+    let mut entity = world
         .create_entity()
         .with(sprite_render)
-        .with(Ball {
-            radius: BALL_RADIUS_COLLISION,
-            velocity: random_45_vec(&Vector3::x_axis(), INITIAL_BALL_SPEED),
-            hidden: Some(2.0),
-            rot_velocity: 0.0,
-        })
+        .with(ball)
         .with(local_transform)
         .with(Transparent)
-        .with(Removal::new(State::Game))
-        .build();
+        .with(Removal::new(state));
+    if let Some([red, green, blue, alpha]) = color {
+        entity = entity.with(Tint(Srgba::new(red, green, blue, alpha)));
+    }
+    entity.build()
 }
 
-/// Initialises a ui scoreboard
-pub fn initialize_scoreboard(world: &mut World) {
-    let font = world.read_resource::<Loader>().load(
-        "font/square.ttf",
-        TtfFormat,
-        (),
-        &world.read_resource(),
+/// The transform of a paddle at the start of a match.
+pub fn paddle_transform(side: Side) -> Transform {
+    let mut transform = Transform::default();
+
+    // Correctly position the paddle.
+    let y = ARENA_HEIGHT / 2.0;
+    let x = match side {
+        Side::Left => PADDLE_WALL_OFFSET + PADDLE_SIZE_COLLISION[0] * 0.5,
+        Side::Right => ARENA_WIDTH - PADDLE_WALL_OFFSET - PADDLE_SIZE_COLLISION[0] * 0.5,
+    };
+    transform.set_translation_xyz(x, y, 0.0);
+    transform.set_scale(
+        [
+            PADDLE_SIZE[0] / PADDLE_TEXTURE_SIZE[0],
+            PADDLE_SIZE[1] / PADDLE_TEXTURE_SIZE[1],
+            1.0,
+        ]
+        .into(),
+    );
+    transform
+}
+
+/// The transform of the ball at the start of a match, in the middle of the arena.
+pub fn ball_transform() -> Transform {
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0, 0.0);
+    transform.set_scale(
+        [
+            2.0 * BALL_RADIUS / BALL_TEXTURE_SIZE[0],
+            2.0 * BALL_RADIUS / BALL_TEXTURE_SIZE[1],
+            1.0,
+        ]
+        .into(),
     );
+    transform
+}
+
+/// A ball waiting for the first serve towards the right with the given `speed`.
+pub fn serve<R: Rng>(rng: &mut R, speed: f32) -> Ball {
+    Ball {
+        radius: BALL_RADIUS_COLLISION,
+        velocity: random_45_vec(rng, &Vector3::x_axis(), speed),
+        hidden: Some(2.0),
+        rot_velocity: 0.0,
+    }
+}
+
+/// Initialises a ui scoreboard in the font and colours of the theme
+pub fn initialize_scoreboard(world: &mut World) {
+    world.register::<ScaledUi>();
+    let (font, score_color, text_color) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (
+            theme.score_font.clone(),
+            theme.score_color,
+            theme.text_color,
+        )
+    };
+    let font = world
+        .read_resource::<Loader>()
+        .load(font, TtfFormat, (), &world.read_resource());
     let p1_transform = UiTransform::new(
         "P1".to_string(),
         Anchor::TopMiddle,
         Anchor::TopMiddle,
         -110.,
-        -20.,
+        -35.,
         1.,
         400.,
         100.,
@@ -269,19 +884,33 @@ pub fn initialize_scoreboard(world: &mut World) {
         Anchor::TopMiddle,
         Anchor::TopMiddle,
         110.,
-        -20.,
+        -35.,
         1.,
         400.,
         100.,
     );
 
+    // The scores take the colours of the sides in a colour-blind palette
+    let score_colors = {
+        let palette = world
+            .entry::<AccessibilityConfig>()
+            .or_insert_with(AccessibilityConfig::default)
+            .palette;
+        let color = |side| match palette.side(side) {
+            Some([red, green, blue, _]) => [red, green, blue, score_color[3]],
+            None => score_color,
+        };
+        [color(Side::Left), color(Side::Right)]
+    };
+
     let p1_score = world
         .create_entity()
+        .with(ScaledUi::new(&p1_transform, 100.))
         .with(p1_transform)
         .with(UiText::new(
             font.clone(),
             "0".to_string(),
-            [1.0, 0.0, 0.0, 0.2],
+            score_colors[0],
             100.,
         ))
         .with(Removal::new(State::Game))
@@ -289,15 +918,69 @@ pub fn initialize_scoreboard(world: &mut World) {
 
     let p2_score = world
         .create_entity()
+        .with(ScaledUi::new(&p2_transform, 100.))
         .with(p2_transform)
         .with(UiText::new(
             font.clone(),
             "0".to_string(),
-            [1.0, 0.0, 0.0, 0.2],
+            score_colors[1],
             100.,
         ))
         .with(Removal::new(State::Game))
         .build();
 
-    world.insert(ScoreText { p1_score, p2_score });
+    // The names of the players sit above their scores
+    let names = {
+        let names = world
+            .entry::<PlayerNames>()
+            .or_insert_with(PlayerNames::default);
+        [
+            ("P1Name", names.left.clone(), -110.),
+            ("P2Name", names.right.clone(), 110.),
+        ]
+    };
+    for (id, name, x) in names.iter() {
+        let transform = UiTransform::new(
+            id.to_string(),
+            Anchor::TopMiddle,
+            Anchor::TopMiddle,
+            *x,
+            -10.,
+            1.,
+            200.,
+            25.,
+        );
+        world
+            .create_entity()
+            .with(ScaledUi::new(&transform, 20.))
+            .with(transform)
+            .with(UiText::new(font.clone(), name.clone(), text_color, 20.))
+            .with(Removal::new(State::Game))
+            .build();
+    }
+
+    // The match clock sits between the two scores
+    let clock_transform = UiTransform::new(
+        "Clock".to_string(),
+        Anchor::TopMiddle,
+        Anchor::TopMiddle,
+        0.,
+        -50.,
+        1.,
+        200.,
+        40.,
+    );
+    let clock = world
+        .create_entity()
+        .with(ScaledUi::new(&clock_transform, 25.))
+        .with(clock_transform)
+        .with(UiText::new(font.clone(), String::new(), text_color, 25.))
+        .with(Removal::new(State::Game))
+        .build();
+
+    world.insert(ScoreText {
+        p1_score,
+        p2_score,
+        clock,
+    });
 }
@@ -0,0 +1,154 @@
+use amethyst::{
+    assets::Handle,
+    ecs::prelude::Entity,
+    prelude::{
+        Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, TransEvent, WorldExt,
+    },
+    shrev::EventChannel,
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use crate::{
+    find_ui,
+    pong::PausedOrRunning,
+    states::{BracketState, CampaignState, MainMenuState, State},
+    tournament::Tournament,
+};
+
+const MENU_LBL_WINNER_ID: &str = "lbl_winner";
+const MENU_LBL_RATINGS_ID: &str = "lbl_ratings";
+const MENU_BTN_CONTINUE_ID: &str = "btn_continue";
+const MENU_BTN_QUIT_ID: &str = "btn_quit";
+const MENU_RON: &str = "ui/game_over.ron";
+
+/// Where to go, once the game over screen is left.
+#[derive(Clone)]
+pub enum Next {
+    MainMenu,
+    Bracket(Tournament),
+    Campaign,
+}
+
+/// Shown on top of the `GameState` once the match is decided.
+pub struct GameOverState {
+    message: String,
+    /// The new ratings of the players, with how much they changed.
+    ratings: String,
+    next: Next,
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    label: Option<Entity>,
+    ratings_label: Option<Entity>,
+    quit: Option<Entity>,
+    proceed: Option<Entity>,
+}
+
+impl GameOverState {
+    pub fn new(message: String, next: Next) -> Self {
+        GameOverState {
+            message,
+            ratings: String::new(),
+            next,
+            ui: None,
+            root: None,
+            label: None,
+            ratings_label: None,
+            quit: None,
+            proceed: None,
+        }
+    }
+    /// Show the changed ratings below the winner.
+    pub fn with_ratings(mut self, ratings: String) -> Self {
+        self.ratings = ratings;
+        self
+    }
+}
+
+impl SimpleState for GameOverState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::GameOver))
+            .build()
+            .into();
+
+        world.insert(PausedOrRunning::Paused);
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.insert(PausedOrRunning::Running);
+
+        exec_removal(&world.entities(), &world.read_storage(), State::GameOver);
+        self.root = None;
+        self.label = None;
+        self.ratings_label = None;
+        self.proceed = None;
+        self.quit = None;
+    }
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use StateEvent::*;
+        match event {
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                if Some(ui_event.target) == self.quit {
+                    SimpleTrans::Quit
+                } else if Some(ui_event.target) == self.proceed {
+                    let next = self.next.clone();
+                    data.world
+                        .write_resource::<EventChannel<TransEvent<GameData<'_, '_>, StateEvent>>>()
+                        .single_write(Box::from(move || match next.clone() {
+                            Next::MainMenu => {
+                                SimpleTrans::Switch(Box::from(MainMenuState::default()))
+                            }
+                            Next::Bracket(tournament) => {
+                                SimpleTrans::Switch(Box::from(BracketState::new(tournament)))
+                            }
+                            Next::Campaign => {
+                                SimpleTrans::Switch(Box::from(CampaignState::default()))
+                            }
+                        }));
+                    SimpleTrans::Pop
+                } else {
+                    SimpleTrans::None
+                }
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.label.is_none()
+            || self.ratings_label.is_none()
+            || self.proceed.is_none()
+            || self.quit.is_none()
+        {
+            self.label = data.world.exec(find_ui(MENU_LBL_WINNER_ID));
+            self.ratings_label = data.world.exec(find_ui(MENU_LBL_RATINGS_ID));
+            self.proceed = data.world.exec(find_ui(MENU_BTN_CONTINUE_ID));
+            self.quit = data.world.exec(find_ui(MENU_BTN_QUIT_ID));
+            if let Some(label) = self.label {
+                if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                    text.text = self.message.clone();
+                }
+            }
+            if let Some(label) = self.ratings_label {
+                if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                    text.text = self.ratings.clone();
+                }
+            }
+        }
+        SimpleTrans::None
+    }
+}
@@ -0,0 +1,107 @@
+use amethyst::{
+    assets::Handle,
+    ecs::prelude::Entity,
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, WorldExt},
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use crate::{
+    find_ui,
+    locale::Locale,
+    paths::profiles_file,
+    profile::Profiles,
+    rating::leaderboard,
+    states::{MainMenuState, State},
+};
+
+const MENU_LBL_LEADERBOARD_ID: &str = "lbl_leaderboard";
+const MENU_BTN_BACK_ID: &str = "btn_back";
+const MENU_RON: &str = "ui/leaderboard.ron";
+/// Number of places shown, the rest doesn't fit on the screen.
+const PLACES: usize = 15;
+
+/// Ranks the players with a profile and the AI levels by their rating.
+#[derive(Default)]
+pub struct LeaderboardState {
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    label: Option<Entity>,
+    back: Option<Entity>,
+}
+
+impl LeaderboardState {
+    fn overview(&self, locale: &Locale) -> String {
+        let standings = leaderboard(&Profiles::load(profiles_file()), locale);
+        let mut lines = vec![locale.get("leaderboard.title").to_string()];
+        for (place, standing) in standings.iter().take(PLACES).enumerate() {
+            let record = match &standing.stats {
+                Some(stats) => format!("  W {}  L {}", stats.wins, stats.losses),
+                None => String::new(),
+            };
+            lines.push(format!(
+                "{}. {}  {:.0}{}",
+                place + 1,
+                standing.name,
+                standing.rating,
+                record
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl SimpleState for LeaderboardState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::Leaderboard))
+            .build()
+            .into();
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        exec_removal(&world.entities(), &world.read_storage(), State::Leaderboard);
+        self.root = None;
+        self.label = None;
+        self.back = None;
+    }
+    fn handle_event(
+        &mut self,
+        _: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use StateEvent::*;
+        match event {
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                if Some(ui_event.target) == self.back {
+                    SimpleTrans::Switch(Box::from(MainMenuState::default()))
+                } else {
+                    SimpleTrans::None
+                }
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.label.is_none() || self.back.is_none() {
+            self.label = data.world.exec(find_ui(MENU_LBL_LEADERBOARD_ID));
+            self.back = data.world.exec(find_ui(MENU_BTN_BACK_ID));
+            if let Some(label) = self.label {
+                if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                    text.text = self.overview(&data.world.read_resource());
+                }
+            }
+        }
+        SimpleTrans::None
+    }
+}
@@ -1,30 +1,144 @@
 use amethyst::{
     assets::Handle,
-    ecs::prelude::Entity,
-    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, WorldExt},
-    ui::{UiEventType, UiLoader, UiPrefab},
+    core::{timing::Time, ArcThreadPool, HiddenPropagate, SystemExt},
+    ecs::{prelude::Entity, Dispatcher},
+    prelude::{
+        Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, World, WorldExt,
+    },
+    ui::{UiEventType, UiImage, UiLoader, UiPrefab},
     utils::removal::{exec_removal, Removal},
 };
 
-use super::{GameState, State};
-use crate::{audio::initialize_audio, find_ui};
+use super::{
+    game::{
+        initialize_ball, initialize_camera, initialize_center_line, initialize_letterbox,
+        initialize_paddle, load_sprite_sheet,
+    },
+    match_dispatcher,
+    menu_focus::MenuFocus,
+    CampaignState, GameState, LeaderboardState, PracticeState, ProfileSelectState, State,
+    TournamentSetupState,
+};
+use crate::{
+    attract::AttractConfig,
+    audio::{initialize_audio, Sounds},
+    effects::initialize_effects,
+    find_ui,
+    paths::match_file,
+    pong::{Ai, Difficulty, PausedOrRunning, ScoreBoard, Side, INITIAL_BALL_SPEED},
+    rules::MatchClock,
+    save::SavedMatch,
+    systems,
+};
+
+use std::fs;
 
+const MENU_BTN_CONTINUE_ID: &'static str = "btn_continue";
 const MENU_BTN_SINGLE_PLAYER_ID: &'static str = "btn_single_player";
+const MENU_BTN_CAMPAIGN_ID: &'static str = "btn_campaign";
 const MENU_BTN_TWO_PLAYER_ID: &'static str = "btn_two_player";
+const MENU_BTN_TOURNAMENT_ID: &'static str = "btn_tournament";
+const MENU_BTN_PRACTICE_ID: &'static str = "btn_practice";
+const MENU_BTN_LEADERBOARD_ID: &'static str = "btn_leaderboard";
 const MENU_BTN_QUIT_ID: &'static str = "btn_quit";
 
+const MENU_ROOT_ID: &'static str = "main_menu_root";
+
 const MENU_RON: &'static str = "ui/main_menu.ron";
 
 #[derive(Default)]
-pub struct MainMenuState {
+pub struct MainMenuState<'a, 'b> {
     ui: Option<Handle<UiPrefab>>,
     root: Option<Entity>,
+    /// The container the buttons are on, its background lets the demo shine through.
+    container: Option<Entity>,
+    proceed: Option<Entity>,
     single_player: Option<Entity>,
+    campaign: Option<Entity>,
     two_player: Option<Entity>,
+    tournament: Option<Entity>,
+    practice: Option<Entity>,
+    leaderboard: Option<Entity>,
     quit: Option<Entity>,
+    focus: MenuFocus,
+    /// Seconds since the last input.
+    idle: f32,
+    /// The AI match of the attract mode, while it plays.
+    demo: Option<Dispatcher<'a, 'b>>,
+    /// Background of the menu and the sound effects, while the demo replaces them.
+    replaced: Option<(UiImage, Option<Sounds>)>,
+}
+
+impl MainMenuState<'_, '_> {
+    /// Play a match between two AIs behind the menu, reusing the systems of a real match.
+    fn start_demo(&mut self, world: &mut World) {
+        let effects = systems::EffectsSystem::new(world);
+        let mut dispatcher = match_dispatcher()
+            .with(
+                effects.pausable(PausedOrRunning::Running),
+                "effects_system",
+                &["collision_system", "winner_system"],
+            )
+            .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
+            .build();
+        dispatcher.setup(world);
+        self.demo = Some(dispatcher);
+
+        // An endless match, without a scoreboard
+        world.insert(ScoreBoard::default());
+        world.insert(MatchClock::default());
+        let sprites = load_sprite_sheet(world);
+        initialize_camera(world, State::Attract);
+        initialize_center_line(world, sprites.clone(), State::Attract);
+        initialize_letterbox(world, sprites.clone(), State::Attract);
+        initialize_ball(world, sprites.clone(), INITIAL_BALL_SPEED, State::Attract);
+        for &(side, difficulty) in &[
+            (Side::Left, Difficulty::Normal),
+            (Side::Right, Difficulty::Hard),
+        ] {
+            let ai = Ai {
+                params: difficulty.ai_params(),
+            };
+            initialize_paddle(world, sprites.clone(), side, Some(ai), State::Attract);
+        }
+        initialize_effects(world, sprites, State::Attract);
+
+        // The demo plays silently behind a lighter menu
+        let background = world
+            .try_fetch::<AttractConfig>()
+            .map(|config| config.menu_background)
+            .unwrap_or_else(|| AttractConfig::default().menu_background);
+        let previous = self.container.and_then(|container| {
+            world
+                .write_storage::<UiImage>()
+                .insert(container, UiImage::SolidColor(background))
+                .ok()
+                .flatten()
+        });
+        if let Some(previous) = previous {
+            self.replaced = Some((previous, world.remove::<Sounds>()));
+        }
+    }
+
+    fn stop_demo(&mut self, world: &mut World) {
+        if self.demo.take().is_none() {
+            return;
+        }
+        exec_removal(&world.entities(), &world.read_storage(), State::Attract);
+        if let Some((background, sounds)) = self.replaced.take() {
+            if let Some(container) = self.container {
+                let _ = world
+                    .write_storage::<UiImage>()
+                    .insert(container, background);
+            }
+            if let Some(sounds) = sounds {
+                world.insert(sounds);
+            }
+        }
+    }
 }
 
-impl SimpleState for MainMenuState {
+impl SimpleState for MainMenuState<'_, '_> {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
         world.register::<Removal<State>>();
@@ -46,26 +160,62 @@ impl SimpleState for MainMenuState {
     fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         // Delete everything we have
         let world = data.world;
+        self.stop_demo(world);
         exec_removal(&world.entities(), &world.read_storage(), State::MainMenu);
         self.root = None;
+        self.container = None;
+        self.proceed = None;
         self.single_player = None;
+        self.campaign = None;
         self.two_player = None;
+        self.tournament = None;
+        self.practice = None;
+        self.leaderboard = None;
         self.quit = None;
+        self.focus = MenuFocus::default();
     }
     fn handle_event(
         &mut self,
-        _: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
         use StateEvent::*;
+        // Any input stops the demo, and is handled as usual
+        if let Input(_) = event {
+            self.idle = 0.0;
+            self.stop_demo(data.world);
+        }
+        self.focus.handle_event(data.world, &event);
         match event {
             Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
                 if Some(ui_event.target) == self.quit {
                     SimpleTrans::Quit
+                } else if Some(ui_event.target) == self.proceed {
+                    match SavedMatch::load(match_file()) {
+                        Ok(saved) => {
+                            // A match can only be continued once
+                            if let Err(e) = fs::remove_file(match_file()) {
+                                eprintln!("Failed to remove the saved match: {}", e);
+                            }
+                            SimpleTrans::Switch(Box::from(GameState::from_save(saved)))
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to load the saved match: {}", e);
+                            SimpleTrans::None
+                        }
+                    }
                 } else if Some(ui_event.target) == self.single_player {
-                    SimpleTrans::Switch(Box::from(GameState::with_single_player()))
+                    SimpleTrans::Switch(Box::from(ProfileSelectState::single_player()))
+                } else if Some(ui_event.target) == self.campaign {
+                    SimpleTrans::Switch(Box::from(CampaignState::default()))
                 } else if Some(ui_event.target) == self.two_player {
-                    SimpleTrans::Switch(Box::from(GameState::with_two_players()))
+                    SimpleTrans::Switch(Box::from(ProfileSelectState::two_players()))
+                } else if Some(ui_event.target) == self.tournament {
+                    SimpleTrans::Switch(Box::from(TournamentSetupState::default()))
+                } else if Some(ui_event.target) == self.practice {
+                    SimpleTrans::Switch(Box::from(PracticeState::default()))
+                } else if Some(ui_event.target) == self.leaderboard {
+                    SimpleTrans::Switch(Box::from(LeaderboardState::default()))
                 } else {
                     SimpleTrans::None
                 }
@@ -74,10 +224,64 @@ impl SimpleState for MainMenuState {
         }
     }
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
-        if self.single_player.is_none() || self.two_player.is_none() || self.quit.is_none() {
+        match self.demo.as_mut() {
+            Some(demo) => demo.dispatch(data.world),
+            None => {
+                self.idle += data.world.read_resource::<Time>().delta_seconds();
+                let delay = data
+                    .world
+                    .try_fetch::<AttractConfig>()
+                    .map_or(AttractConfig::default().delay, |config| config.delay);
+                if delay.map_or(false, |delay| self.idle >= delay) {
+                    self.start_demo(data.world);
+                }
+            }
+        }
+
+        if self.container.is_none()
+            || self.proceed.is_none()
+            || self.single_player.is_none()
+            || self.campaign.is_none()
+            || self.two_player.is_none()
+            || self.tournament.is_none()
+            || self.practice.is_none()
+            || self.leaderboard.is_none()
+            || self.quit.is_none()
+        {
+            self.container = data.world.exec(find_ui(MENU_ROOT_ID));
+            self.proceed = data.world.exec(find_ui(MENU_BTN_CONTINUE_ID));
             self.single_player = data.world.exec(find_ui(MENU_BTN_SINGLE_PLAYER_ID));
+            self.campaign = data.world.exec(find_ui(MENU_BTN_CAMPAIGN_ID));
             self.two_player = data.world.exec(find_ui(MENU_BTN_TWO_PLAYER_ID));
+            self.tournament = data.world.exec(find_ui(MENU_BTN_TOURNAMENT_ID));
+            self.practice = data.world.exec(find_ui(MENU_BTN_PRACTICE_ID));
+            self.leaderboard = data.world.exec(find_ui(MENU_BTN_LEADERBOARD_ID));
             self.quit = data.world.exec(find_ui(MENU_BTN_QUIT_ID));
+            self.focus = MenuFocus::new(
+                [
+                    self.proceed,
+                    self.single_player,
+                    self.campaign,
+                    self.two_player,
+                    self.tournament,
+                    self.practice,
+                    self.leaderboard,
+                    self.quit,
+                ]
+                .iter()
+                .flatten()
+                .copied()
+                .collect(),
+            );
+            // Only offer to continue, if there is something to continue
+            if let Some(proceed) = self.proceed {
+                if !match_file().exists() {
+                    data.world
+                        .write_storage::<HiddenPropagate>()
+                        .insert(proceed, HiddenPropagate)
+                        .expect("Button is alive");
+                }
+            }
         }
         SimpleTrans::None
     }
@@ -0,0 +1,101 @@
+//! Moving through the buttons of a menu with the keyboard or a gamepad.
+use amethyst::{
+    core::HiddenPropagate,
+    ecs::prelude::Entity,
+    input::InputEvent,
+    prelude::{StateEvent, World, WorldExt},
+    shrev::EventChannel,
+    ui::{UiEvent, UiEventType},
+};
+
+const ACTION_UP: &str = "menu_up";
+const ACTION_DOWN: &str = "menu_down";
+const ACTION_CONFIRM: &str = "menu_confirm";
+const ACTION_BACK: &str = "menu_back";
+
+/// The button of a menu that has the focus, highlighted like a button under the mouse.
+/// Confirming clicks it, so the menus handle it like any other click.
+#[derive(Default)]
+pub struct MenuFocus {
+    /// The buttons of the menu, from top to bottom.
+    buttons: Vec<Entity>,
+    focused: Option<usize>,
+}
+
+impl MenuFocus {
+    pub fn new(buttons: Vec<Entity>) -> Self {
+        MenuFocus {
+            buttons,
+            focused: None,
+        }
+    }
+
+    /// Move the focus or click the focused button, returns whether the player wants to go back.
+    pub fn handle_event(&mut self, world: &World, event: &StateEvent) -> bool {
+        match event {
+            StateEvent::Input(InputEvent::ActionPressed(action)) => match action.as_str() {
+                ACTION_UP => self.step(world, false),
+                ACTION_DOWN => self.step(world, true),
+                ACTION_CONFIRM => {
+                    if let Some(&button) = self.focused.and_then(|index| self.buttons.get(index)) {
+                        send(world, UiEventType::Click, button);
+                    }
+                }
+                ACTION_BACK => return true,
+                _ => (),
+            },
+            // The mouse takes the focus along
+            StateEvent::Ui(ui_event) if ui_event.event_type == UiEventType::HoverStart => {
+                if let Some(index) = self.buttons.iter().position(|&b| b == ui_event.target) {
+                    if self.focused != Some(index) {
+                        self.unfocus(world);
+                        self.focused = Some(index);
+                    }
+                }
+            }
+            _ => (),
+        }
+        false
+    }
+
+    /// Focus the next visible button below or above, wrapping around.
+    /// Without a focus yet, the first visible button gets it.
+    fn step(&mut self, world: &World, down: bool) {
+        let count = self.buttons.len();
+        if count == 0 {
+            return;
+        }
+        let next = {
+            let hidden = world.read_storage::<HiddenPropagate>();
+            let (buttons, focused) = (&self.buttons, self.focused);
+            (1..=count)
+                .map(|step| match focused {
+                    Some(index) if down => (index + step) % count,
+                    Some(index) => (index + count - step) % count,
+                    None => step - 1,
+                })
+                .find(|&index| !hidden.contains(buttons[index]))
+        };
+        if next.is_some() && next != self.focused {
+            self.unfocus(world);
+            self.focused = next;
+            if let Some(index) = next {
+                send(world, UiEventType::HoverStart, self.buttons[index]);
+            }
+        }
+    }
+
+    fn unfocus(&mut self, world: &World) {
+        if let Some(&button) = self.focused.and_then(|index| self.buttons.get(index)) {
+            send(world, UiEventType::HoverStop, button);
+        }
+        self.focused = None;
+    }
+}
+
+/// Pretend the mouse did something with the button, to use its hover look and click handling.
+fn send(world: &World, event_type: UiEventType, button: Entity) {
+    world
+        .write_resource::<EventChannel<UiEvent>>()
+        .single_write(UiEvent::new(event_type, button));
+}
@@ -1,15 +1,43 @@
 //! The possible game states
+mod bracket;
+mod campaign;
+mod confirm;
 mod game;
+mod game_over;
+mod leaderboard;
 mod main_menu;
+mod menu_focus;
 mod pause;
+mod practice;
+mod profile_select;
+mod tournament_setup;
 
-pub use game::GameState;
+pub use bracket::BracketState;
+pub use campaign::CampaignState;
+pub use confirm::{confirm, ConfirmState};
+pub use game::{
+    ball_transform, game_dispatcher, match_dispatcher, paddle_transform, serve, GameState, Mode,
+};
+pub use game_over::{GameOverState, Next};
+pub use leaderboard::LeaderboardState;
 pub use main_menu::MainMenuState;
 pub use pause::PauseState;
+pub use practice::PracticeState;
+pub use profile_select::ProfileSelectState;
+pub use tournament_setup::TournamentSetupState;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum State {
     MainMenu,
     Game,
     Pause,
+    GameOver,
+    TournamentSetup,
+    Bracket,
+    Practice,
+    Campaign,
+    ProfileSelect,
+    Leaderboard,
+    Attract,
+    Confirm,
 }
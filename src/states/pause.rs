@@ -1,34 +1,94 @@
 use amethyst::{
     assets::Handle,
+    core::{timing::Time, HiddenPropagate},
     ecs::prelude::Entity,
     input::InputEvent,
     prelude::{
-        Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, TransEvent, WorldExt,
+        Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, World, WorldExt,
     },
-    shrev::EventChannel,
-    ui::{UiEventType, UiLoader, UiPrefab},
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
     utils::removal::{exec_removal, Removal},
 };
 
+use super::{confirm, menu_focus::MenuFocus};
 use crate::{
     find_ui,
-    pong::PausedOrRunning,
-    states::{MainMenuState, State},
+    paths::match_file,
+    pause::PauseConfig,
+    pong::{pause_requested, PausedOrRunning},
+    save::SavedMatch,
+    states::{MainMenuState, Mode, State},
 };
 
 const MENU_BTN_MAIN_MENU_ID: &str = "btn_main_menu";
 const MENU_BTN_RESUME_ID: &str = "btn_resume";
+const MENU_BTN_SAVE_QUIT_ID: &str = "btn_save_quit";
 const MENU_BTN_QUIT_ID: &str = "btn_quit";
+const MENU_LBL_COUNTDOWN_ID: &str = "lbl_countdown";
 const MENU_RON: &str = "ui/pause.ron";
 const ACTION_PAUSE: &str = "pause";
 
 #[derive(Default)]
 pub struct PauseState {
+    /// The mode of the paused match, if it can be saved.
+    save: Option<Mode>,
     ui: Option<Handle<UiPrefab>>,
     root: Option<Entity>,
     resume: Option<Entity>,
+    save_quit: Option<Entity>,
     quit: Option<Entity>,
     main_menu: Option<Entity>,
+    countdown_label: Option<Entity>,
+    focus: MenuFocus,
+    /// Seconds left until the match goes on, after resuming.
+    countdown: Option<f32>,
+}
+
+impl PauseState {
+    /// Pause a match, that can be saved and continued later.
+    pub fn with_save(mode: Mode) -> Self {
+        PauseState {
+            save: Some(mode),
+            ..Default::default()
+        }
+    }
+
+    /// Go back to the match, after counting down if configured.
+    fn resume_match(&mut self, world: &World) -> SimpleTrans {
+        let seconds = world
+            .try_fetch::<PauseConfig>()
+            .map(|config| config.resume_countdown)
+            .unwrap_or_else(|| PauseConfig::default().resume_countdown);
+        if seconds <= 0.0 {
+            return SimpleTrans::Pop;
+        }
+        self.countdown = Some(seconds);
+        self.show_menu(world, false);
+        SimpleTrans::None
+    }
+
+    /// Show the buttons, or hide them for the countdown.
+    fn show_menu(&self, world: &World, visible: bool) {
+        let mut hidden = world.write_storage::<HiddenPropagate>();
+        // Practice can't be saved, its button stays hidden
+        let save_quit = self.save_quit.filter(|_| self.save.is_some());
+        for button in [self.resume, save_quit, self.main_menu, self.quit]
+            .iter()
+            .flatten()
+        {
+            if visible {
+                hidden.remove(*button);
+            } else {
+                hidden
+                    .insert(*button, HiddenPropagate)
+                    .expect("Button is alive");
+            }
+        }
+        let mut texts = world.write_storage::<UiText>();
+        if let Some(text) = self.countdown_label.and_then(|label| texts.get_mut(label)) {
+            text.text.clear();
+        }
+    }
 }
 
 impl SimpleState for PauseState {
@@ -59,6 +119,10 @@ impl SimpleState for PauseState {
         self.main_menu = None;
         self.quit = None;
         self.resume = None;
+        self.save_quit = None;
+        self.countdown_label = None;
+        self.focus = MenuFocus::default();
+        self.countdown = None;
     }
     fn handle_event(
         &mut self,
@@ -67,20 +131,51 @@ impl SimpleState for PauseState {
     ) -> SimpleTrans {
         use InputEvent::*;
         use StateEvent::*;
+        if self.countdown.is_some() {
+            // Pausing again stops the countdown
+            if pause_requested(&event, &data.world.read_resource()) {
+                self.countdown = None;
+                self.show_menu(data.world, true);
+            }
+            return SimpleTrans::None;
+        }
+        // Going back from the pause menu resumes the match
+        if self.focus.handle_event(data.world, &event) {
+            return self.resume_match(data.world);
+        }
         match event {
-            Input(ActionPressed(action)) if action == ACTION_PAUSE => SimpleTrans::Pop,
+            Input(ActionPressed(action)) if action == ACTION_PAUSE => self.resume_match(data.world),
             Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
                 if Some(ui_event.target) == self.quit {
-                    SimpleTrans::Quit
+                    confirm(
+                        data.world,
+                        "confirm.quit_match",
+                        vec![Box::from(|| SimpleTrans::Quit)],
+                    )
                 } else if Some(ui_event.target) == self.resume {
-                    SimpleTrans::Pop
+                    self.resume_match(data.world)
+                } else if Some(ui_event.target) == self.save_quit {
+                    let mode = match self.save.clone() {
+                        Some(mode) => mode,
+                        None => return SimpleTrans::None,
+                    };
+                    match SavedMatch::capture(data.world, mode).save(match_file()) {
+                        Ok(()) => SimpleTrans::Quit,
+                        Err(e) => {
+                            eprintln!("Failed to save the match: {}", e);
+                            SimpleTrans::None
+                        }
+                    }
                 } else if Some(ui_event.target) == self.main_menu {
-                    data.world
-                        .write_resource::<EventChannel<TransEvent<GameData<'_, '_>, StateEvent>>>()
-                        .single_write(Box::from(|| {
-                            SimpleTrans::Switch(Box::from(MainMenuState::default()))
-                        }));
-                    SimpleTrans::Pop
+                    // Leave the pause menu, then the match
+                    confirm(
+                        data.world,
+                        "confirm.leave_match",
+                        vec![
+                            Box::from(|| SimpleTrans::Pop),
+                            Box::from(|| SimpleTrans::Switch(Box::from(MainMenuState::default()))),
+                        ],
+                    )
                 } else {
                     SimpleTrans::None
                 }
@@ -89,10 +184,44 @@ impl SimpleState for PauseState {
         }
     }
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
-        if self.main_menu.is_none() || self.resume.is_none() || self.quit.is_none() {
+        // Real time, the match may run slower
+        if let Some(countdown) = self.countdown {
+            let countdown = countdown - data.world.read_resource::<Time>().delta_real_seconds();
+            if countdown <= 0.0 {
+                return SimpleTrans::Pop;
+            }
+            self.countdown = Some(countdown);
+            let mut texts = data.world.write_storage::<UiText>();
+            if let Some(text) = self.countdown_label.and_then(|label| texts.get_mut(label)) {
+                text.text = countdown.ceil().to_string();
+            }
+        }
+
+        if self.main_menu.is_none()
+            || self.resume.is_none()
+            || self.save_quit.is_none()
+            || self.quit.is_none()
+            || self.countdown_label.is_none()
+        {
             self.main_menu = data.world.exec(find_ui(MENU_BTN_MAIN_MENU_ID));
             self.resume = data.world.exec(find_ui(MENU_BTN_RESUME_ID));
+            self.save_quit = data.world.exec(find_ui(MENU_BTN_SAVE_QUIT_ID));
             self.quit = data.world.exec(find_ui(MENU_BTN_QUIT_ID));
+            self.countdown_label = data.world.exec(find_ui(MENU_LBL_COUNTDOWN_ID));
+            self.focus = MenuFocus::new(
+                [self.resume, self.save_quit, self.main_menu, self.quit]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect(),
+            );
+            // Practice can't be saved
+            if let (Some(save_quit), None) = (self.save_quit, &self.save) {
+                data.world
+                    .write_storage::<HiddenPropagate>()
+                    .insert(save_quit, HiddenPropagate)
+                    .expect("Button is alive");
+            }
         }
         SimpleTrans::None
     }
@@ -0,0 +1,253 @@
+use amethyst::{
+    assets::{Handle, Loader},
+    core::{transform::Transform, ArcThreadPool, Hidden, SystemExt},
+    ecs::{prelude::Entity, world::Builder, Dispatcher, DispatcherBuilder, Join},
+    input::InputEvent,
+    prelude::{GameData, SimpleState, SimpleTrans, StateData, StateEvent, World, WorldExt},
+    renderer::{transparent::Transparent, SpriteRender, SpriteSheet},
+    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    utils::removal::{exec_removal, Removal},
+};
+
+use crate::{
+    accessibility::set_game_speed,
+    effects::initialize_effects,
+    hud::{initialize_spin_hud, HudConfig},
+    pong::{
+        pause_requested, Ball, GameRng, PausedOrRunning, ScaledUi, Side, ARENA_WIDTH,
+        INITIAL_BALL_SPEED, PADDLE_SIZE, PADDLE_TEXTURE_SIZE, PADDLE_WALL_OFFSET,
+    },
+    practice::{Practice, PracticeConfig, PracticeText},
+    states::{
+        game::{
+            initialize_ball, initialize_camera, initialize_letterbox, initialize_paddle,
+            load_sprite_sheet,
+        },
+        PauseState, State,
+    },
+    systems,
+    theme::Theme,
+};
+
+const ACTION_RESET: &str = "reset";
+const ACTION_NEXT_DRILL: &str = "next_drill";
+
+/// Drills against a wall or a ball launcher, without touching the `ScoreBoard`.
+#[derive(Default)]
+pub struct PracticeState<'a, 'b> {
+    dispatcher: Option<Dispatcher<'a, 'b>>,
+    target: Option<Entity>,
+}
+
+impl PracticeState<'_, '_> {
+    /// Start the current drill over and launch a fresh ball.
+    fn reset(&self, world: &mut World) {
+        let practice = world.read_resource::<Practice>();
+        let mut rng = world.write_resource::<GameRng>();
+        let mut balls = world.write_storage::<Ball>();
+        let mut transforms = world.write_storage::<Transform>();
+        for (ball, transform) in (&mut balls, &mut transforms).join() {
+            practice.launch(&mut rng.0, ball, transform);
+        }
+    }
+
+    /// Move the target marker to the target of the current drill, or hide it.
+    fn place_target(&self, world: &mut World) {
+        let target = match self.target {
+            Some(target) => target,
+            None => return,
+        };
+        let range = world
+            .read_resource::<Practice>()
+            .current_drill()
+            .and_then(|drill| drill.target());
+        let mut hidden = world.write_storage::<Hidden>();
+        match range {
+            Some((low, high)) => {
+                if let Some(transform) = world.write_storage::<Transform>().get_mut(target) {
+                    transform.set_translation_y((low + high) / 2.0);
+                    transform.set_scale(
+                        [
+                            PADDLE_SIZE[0] / PADDLE_TEXTURE_SIZE[0],
+                            (high - low) / PADDLE_TEXTURE_SIZE[1],
+                            1.0,
+                        ]
+                        .into(),
+                    );
+                }
+                hidden.remove(target);
+            }
+            None => {
+                hidden
+                    .insert(target, Hidden)
+                    .expect("Target marker is alive");
+            }
+        }
+    }
+}
+
+impl SimpleState for PracticeState<'_, '_> {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+        world.register::<Hidden>();
+        let config = world
+            .try_fetch::<PracticeConfig>()
+            .map(|config| (*config).clone())
+            .unwrap_or_default();
+        world.insert(Practice::new(config));
+
+        let effects = systems::EffectsSystem::new(world);
+        let juice = systems::JuiceSystem::new(world);
+        let mut dispatcher = DispatcherBuilder::new()
+            .with(
+                systems::PaddleSystem.pausable(PausedOrRunning::Running),
+                "paddle_system",
+                &[],
+            )
+            .with(
+                systems::MoveBallsSystem.pausable(PausedOrRunning::Running),
+                "ball_system",
+                &[],
+            )
+            .with(
+                systems::BounceSystem.pausable(PausedOrRunning::Running),
+                "collision_system",
+                &["paddle_system", "ball_system"],
+            )
+            .with(
+                systems::PracticeSystem.pausable(PausedOrRunning::Running),
+                "practice_system",
+                &["ball_system"],
+            )
+            .with(
+                effects.pausable(PausedOrRunning::Running),
+                "effects_system",
+                &["collision_system"],
+            )
+            .with(juice, "juice_system", &["collision_system"])
+            .with(
+                systems::SpinHudSystem.pausable(PausedOrRunning::Running),
+                "spin_hud_system",
+                &["collision_system", "practice_system"],
+            )
+            .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
+            .build();
+        dispatcher.setup(world);
+        self.dispatcher = Some(dispatcher);
+
+        let sprites = load_sprite_sheet(world);
+        initialize_practice_text(world);
+        initialize_camera(world, State::Practice);
+        initialize_letterbox(world, sprites.clone(), State::Practice);
+        initialize_ball(world, sprites.clone(), INITIAL_BALL_SPEED, State::Practice);
+        initialize_paddle(world, sprites.clone(), Side::Left, None, State::Practice);
+        initialize_effects(world, sprites.clone(), State::Practice);
+        let curve_preview = world
+            .try_fetch::<HudConfig>()
+            .map_or(true, |config| config.curve_preview);
+        initialize_spin_hud(world, sprites.clone(), State::Practice, curve_preview);
+        self.target = Some(initialize_target(world, sprites));
+        self.place_target(world);
+        self.reset(world);
+        set_game_speed(world, true);
+    }
+    // The menus on top of the match run at normal speed
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        set_game_speed(data.world, false);
+    }
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        set_game_speed(data.world, true);
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        set_game_speed(world, false);
+        exec_removal(&world.entities(), &world.read_storage(), State::Practice);
+        self.target = None;
+    }
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use InputEvent::*;
+        use StateEvent::*;
+        if pause_requested(&event, &data.world.read_resource()) {
+            return SimpleTrans::Push(Box::from(PauseState::default()));
+        }
+        match event {
+            Input(ActionPressed(action)) if action == ACTION_RESET => {
+                data.world.write_resource::<Practice>().reset();
+                self.reset(data.world);
+            }
+            Input(ActionPressed(action)) if action == ACTION_NEXT_DRILL => {
+                data.world.write_resource::<Practice>().next_drill();
+                self.place_target(data.world);
+                self.reset(data.world);
+            }
+            _ => {}
+        }
+        SimpleTrans::None
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if let Some(dispatcher) = self.dispatcher.as_mut() {
+            dispatcher.dispatch(&data.world);
+        }
+
+        SimpleTrans::None
+    }
+}
+
+/// Initialises the marker showing the target on the right wall.
+/// It uses the paddle sprite, but has no `Paddle`, so the ball passes through.
+fn initialize_target(world: &mut World, sprite_sheet: Handle<SpriteSheet>) -> Entity {
+    let sprite_number = world
+        .entry::<Theme>()
+        .or_insert_with(Theme::default)
+        .sprites
+        .right_paddle;
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(ARENA_WIDTH - PADDLE_WALL_OFFSET, 0.0, -0.1);
+
+    world
+        .create_entity()
+        .with(SpriteRender {
+            sprite_sheet,
+            sprite_number,
+        })
+        .with(transform)
+        .with(Transparent)
+        .with(Removal::new(State::Practice))
+        .build()
+}
+
+/// Initialises the ui text showing the drill progress.
+fn initialize_practice_text(world: &mut World) {
+    world.register::<ScaledUi>();
+    let (font, color) = {
+        let theme = world.entry::<Theme>().or_insert_with(Theme::default);
+        (theme.score_font.clone(), theme.text_color)
+    };
+    let font = world
+        .read_resource::<Loader>()
+        .load(font, TtfFormat, (), &world.read_resource());
+    let transform = UiTransform::new(
+        "Practice".to_string(),
+        Anchor::TopMiddle,
+        Anchor::TopMiddle,
+        0.,
+        -20.,
+        1.,
+        480.,
+        30.,
+    );
+    let status = world
+        .create_entity()
+        .with(ScaledUi::new(&transform, 20.))
+        .with(transform)
+        .with(UiText::new(font, String::new(), color, 20.))
+        .with(Removal::new(State::Practice))
+        .build();
+
+    world.insert(PracticeText { status });
+}
@@ -0,0 +1,361 @@
+use amethyst::{
+    assets::{AssetStorage, Handle, Loader},
+    core::HiddenPropagate,
+    ecs::prelude::Entity,
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, WorldExt},
+    renderer::{ImageFormat, Texture},
+    ui::{UiEventType, UiImage, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use crate::{
+    find_ui,
+    locale::Locale,
+    paths::profiles_file,
+    profile::{Lineup, Profile, Profiles, AVATARS},
+    states::{GameState, MainMenuState, State},
+};
+
+const MENU_BTN_START_ID: &str = "btn_start";
+const MENU_BTN_BACK_ID: &str = "btn_back";
+const MENU_RON: &str = "ui/profile_select.ron";
+
+/// Ids of the UI elements of one side, in the order of the fields of `SideUi`.
+const LEFT_IDS: [&str; 8] = [
+    "lbl_title_left",
+    "txt_name_left",
+    "btn_next_left",
+    "img_avatar_left",
+    "btn_avatar_left",
+    "btn_color_left",
+    "lbl_color_left",
+    "lbl_stats_left",
+];
+const RIGHT_IDS: [&str; 8] = [
+    "lbl_title_right",
+    "txt_name_right",
+    "btn_next_right",
+    "img_avatar_right",
+    "btn_avatar_right",
+    "btn_color_right",
+    "lbl_color_right",
+    "lbl_stats_right",
+];
+
+/// The UI elements to pick the profile of one side with.
+#[derive(Default)]
+struct SideUi {
+    title: Option<Entity>,
+    name: Option<Entity>,
+    next: Option<Entity>,
+    avatar_image: Option<Entity>,
+    avatar: Option<Entity>,
+    color: Option<Entity>,
+    color_label: Option<Entity>,
+    stats: Option<Entity>,
+}
+
+impl SideUi {
+    fn find(data: &mut StateData<'_, GameData<'_, '_>>, ids: [&'static str; 8]) -> Self {
+        let mut find = |id| data.world.exec(find_ui(id));
+        SideUi {
+            title: find(ids[0]),
+            name: find(ids[1]),
+            next: find(ids[2]),
+            avatar_image: find(ids[3]),
+            avatar: find(ids[4]),
+            color: find(ids[5]),
+            color_label: find(ids[6]),
+            stats: find(ids[7]),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.all().iter().all(Option::is_some)
+    }
+
+    fn all(&self) -> [Option<Entity>; 8] {
+        [
+            self.title,
+            self.name,
+            self.next,
+            self.avatar_image,
+            self.avatar,
+            self.color,
+            self.color_label,
+            self.stats,
+        ]
+    }
+}
+
+/// Pick a named profile for each player before a match, or create a new one by typing a name.
+/// Without a name, a side plays as a guest.
+pub struct ProfileSelectState {
+    two_players: bool,
+    profiles: Profiles,
+    /// The profiles picked for the left and the right side, with their unsaved changes.
+    picked: [Profile; 2],
+    avatars: Vec<Handle<Texture>>,
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    sides: [SideUi; 2],
+    start: Option<Entity>,
+    back: Option<Entity>,
+}
+
+impl ProfileSelectState {
+    /// Pick the profile of the left player, the right paddle is played by the AI.
+    pub fn single_player() -> Self {
+        ProfileSelectState::new(false)
+    }
+    /// Pick the profiles of both players.
+    pub fn two_players() -> Self {
+        ProfileSelectState::new(true)
+    }
+    fn new(two_players: bool) -> Self {
+        ProfileSelectState {
+            two_players,
+            profiles: Profiles::default(),
+            picked: [Profile::new(String::new()), Profile::new(String::new())],
+            avatars: Vec::new(),
+            ui: None,
+            root: None,
+            sides: Default::default(),
+            start: None,
+            back: None,
+        }
+    }
+
+    /// Switch the side to the profile called `name`, or a new one with the looks of the current.
+    fn pick(&mut self, side: usize, name: &str) {
+        self.picked[side] = match self.profiles.get(name) {
+            Some(profile) => profile.clone(),
+            None => Profile {
+                avatar: self.picked[side].avatar.clone(),
+                paddle_color: self.picked[side].paddle_color,
+                controls: self.picked[side].controls.clone(),
+                ..Profile::new(name.to_string())
+            },
+        };
+    }
+
+    /// Name typed into the text field of the side.
+    fn typed_name(&self, data: &StateData<'_, GameData<'_, '_>>, side: usize) -> Option<String> {
+        let texts = data.world.read_storage::<UiText>();
+        self.sides[side]
+            .name
+            .and_then(|name| texts.get(name))
+            .map(|text| text.text.trim().to_string())
+    }
+
+    /// Show the profile picked for the side.
+    fn show(&self, data: &StateData<'_, GameData<'_, '_>>, side: usize) {
+        let ui = &self.sides[side];
+        let profile = &self.picked[side];
+        let locale = data.world.read_resource::<Locale>();
+        let mut texts = data.world.write_storage::<UiText>();
+        if let Some(text) = ui.name.and_then(|name| texts.get_mut(name)) {
+            if text.text.trim() != profile.name {
+                text.text = profile.name.clone();
+            }
+        }
+        if let Some(text) = ui.color_label.and_then(|label| texts.get_mut(label)) {
+            match profile.paddle_color {
+                Some(color) => {
+                    text.text = locale.get("profile_select.paddle_colour").to_string();
+                    text.color = color;
+                }
+                None => {
+                    text.text = locale.get("profile_select.theme_colour").to_string();
+                    text.color = [0.9, 0.9, 0.9, 1.0];
+                }
+            }
+        }
+        if let Some(text) = ui.stats.and_then(|stats| texts.get_mut(stats)) {
+            text.text = if profile.name.is_empty() {
+                locale.get("profile_select.guest").to_string()
+            } else if self.profiles.get(&profile.name).is_none() {
+                locale.get("profile_select.new_profile").to_string()
+            } else {
+                let stats = &profile.stats;
+                format!("W {}  L {}", stats.wins, stats.losses)
+            };
+        }
+
+        let avatar = AVATARS
+            .iter()
+            .position(|avatar| *avatar == profile.avatar)
+            .and_then(|index| self.avatars.get(index));
+        if let (Some(image), Some(avatar)) = (ui.avatar_image, avatar) {
+            data.world
+                .write_storage::<UiImage>()
+                .insert(image, UiImage::Texture(avatar.clone()))
+                .expect("Image is alive");
+        }
+    }
+
+    /// Save the picked profiles that have a name and put them into a lineup.
+    fn lineup(&mut self) -> Option<Lineup> {
+        let sides = if self.two_players { 2 } else { 1 };
+        let names = self.picked[..sides]
+            .iter()
+            .map(|profile| profile.name.clone())
+            .collect::<Vec<_>>();
+        if sides == 2 && !names[0].is_empty() && names[0] == names[1] {
+            eprintln!("Both players can't play as {}", names[0]);
+            return None;
+        }
+        for profile in self.picked[..sides].iter() {
+            if !profile.name.is_empty() {
+                *self.profiles.get_or_create(&profile.name) = profile.clone();
+            }
+        }
+        if let Err(e) = self.profiles.save(profiles_file()) {
+            eprintln!("Failed to save the profiles: {}", e);
+        }
+        let name = |side: usize| names.get(side).filter(|name| !name.is_empty()).cloned();
+        Some(Lineup {
+            left: name(0),
+            right: name(1),
+        })
+    }
+
+    /// Leave only the title on the right, telling that the AI plays there.
+    fn hide_right_side(&self, data: &StateData<'_, GameData<'_, '_>>) {
+        let right = &self.sides[1];
+        let mut texts = data.world.write_storage::<UiText>();
+        if let Some(text) = right.title.and_then(|title| texts.get_mut(title)) {
+            text.text = data
+                .world
+                .read_resource::<Locale>()
+                .get("profile_select.computer")
+                .to_string();
+        }
+        let mut hidden = data.world.write_storage::<HiddenPropagate>();
+        for entity in right.all().iter().skip(1).flatten() {
+            hidden
+                .insert(*entity, HiddenPropagate)
+                .expect("UI is alive");
+        }
+    }
+}
+
+impl SimpleState for ProfileSelectState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+
+        // Start with the first profiles, the right player takes the second one
+        self.profiles = Profiles::load(profiles_file());
+        for (side, profile) in self.profiles.profiles.iter().take(2).enumerate() {
+            self.picked[side] = profile.clone();
+        }
+        self.avatars = {
+            let loader = world.read_resource::<Loader>();
+            let textures = world.read_resource::<AssetStorage<Texture>>();
+            AVATARS
+                .iter()
+                .map(|avatar| loader.load(*avatar, ImageFormat::default(), (), &textures))
+                .collect()
+        };
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::ProfileSelect))
+            .build()
+            .into();
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        exec_removal(
+            &world.entities(),
+            &world.read_storage(),
+            State::ProfileSelect,
+        );
+        self.root = None;
+        self.sides = Default::default();
+        self.start = None;
+        self.back = None;
+    }
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use StateEvent::*;
+        match event {
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                let target = Some(ui_event.target);
+                if target == self.back {
+                    return SimpleTrans::Switch(Box::from(MainMenuState::default()));
+                } else if target == self.start {
+                    return match self.lineup() {
+                        Some(lineup) if self.two_players => SimpleTrans::Switch(Box::from(
+                            GameState::with_two_players().with_lineup(lineup),
+                        )),
+                        Some(lineup) => SimpleTrans::Switch(Box::from(
+                            GameState::with_single_player().with_lineup(lineup),
+                        )),
+                        None => SimpleTrans::None,
+                    };
+                }
+                for side in 0..2 {
+                    if target == self.sides[side].next {
+                        let next = self
+                            .profiles
+                            .next_name(&self.picked[side].name)
+                            .map(String::from);
+                        if let Some(next) = next {
+                            self.pick(side, &next);
+                        }
+                    } else if target == self.sides[side].avatar {
+                        self.picked[side].next_avatar();
+                    } else if target == self.sides[side].color {
+                        self.picked[side].next_paddle_color();
+                    } else {
+                        continue;
+                    }
+                    self.show(&data, side);
+                }
+                SimpleTrans::None
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if !self.sides[0].is_complete()
+            || !self.sides[1].is_complete()
+            || self.start.is_none()
+            || self.back.is_none()
+        {
+            self.sides = [SideUi::find(data, LEFT_IDS), SideUi::find(data, RIGHT_IDS)];
+            self.start = data.world.exec(find_ui(MENU_BTN_START_ID));
+            self.back = data.world.exec(find_ui(MENU_BTN_BACK_ID));
+            if self.sides[0].is_complete() && self.sides[1].is_complete() {
+                self.show(data, 0);
+                self.show(data, 1);
+                if !self.two_players {
+                    self.hide_right_side(data);
+                }
+            }
+            return SimpleTrans::None;
+        }
+
+        // Follow the names typed into the text fields
+        for side in 0..2 {
+            if let Some(name) = self.typed_name(data, side) {
+                if name != self.picked[side].name {
+                    self.pick(side, &name);
+                    self.show(data, side);
+                }
+            }
+        }
+        SimpleTrans::None
+    }
+}
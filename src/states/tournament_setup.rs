@@ -0,0 +1,162 @@
+use amethyst::{
+    assets::Handle,
+    ecs::prelude::Entity,
+    prelude::{Builder, GameData, SimpleState, SimpleTrans, StateData, StateEvent, WorldExt},
+    ui::{UiEventType, UiLoader, UiPrefab, UiText},
+    utils::removal::{exec_removal, Removal},
+};
+
+use crate::{
+    find_ui,
+    locale::Locale,
+    paths::tournament_file,
+    states::{BracketState, MainMenuState, State},
+    tournament::{Format, Tournament},
+};
+
+const MENU_TXT_NAMES_ID: &str = "txt_names";
+const MENU_LBL_FORMAT_ID: &str = "lbl_format";
+const MENU_BTN_FORMAT_ID: &str = "btn_format";
+const MENU_BTN_START_ID: &str = "btn_start";
+const MENU_BTN_RESUME_ID: &str = "btn_resume_tournament";
+const MENU_BTN_BACK_ID: &str = "btn_back";
+const MENU_RON: &str = "ui/tournament_setup.ron";
+
+/// Enter the players and choose the format of a new tournament,
+/// or continue the saved one.
+#[derive(Default)]
+pub struct TournamentSetupState {
+    format: Format,
+    ui: Option<Handle<UiPrefab>>,
+    root: Option<Entity>,
+    names: Option<Entity>,
+    format_label: Option<Entity>,
+    change_format: Option<Entity>,
+    start: Option<Entity>,
+    resume: Option<Entity>,
+    back: Option<Entity>,
+}
+
+impl TournamentSetupState {
+    fn show_format(&self, data: &StateData<'_, GameData<'_, '_>>) {
+        if let Some(label) = self.format_label {
+            if let Some(text) = data.world.write_storage::<UiText>().get_mut(label) {
+                let locale = data.world.read_resource::<Locale>();
+                text.text =
+                    locale.format("tournament_setup.format", &[self.format.display(&locale)]);
+            }
+        }
+    }
+
+    /// Players entered in the text field, empty names are skipped.
+    fn players(&self, data: &StateData<'_, GameData<'_, '_>>) -> Vec<String> {
+        let storage = data.world.read_storage::<UiText>();
+        self.names
+            .and_then(|names| storage.get(names))
+            .map(|text| {
+                text.text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl SimpleState for TournamentSetupState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.register::<Removal<State>>();
+
+        if self.ui.is_none() {
+            self.ui = world
+                .exec(|loader: UiLoader<'_>| loader.load(MENU_RON, ()))
+                .into();
+        }
+        self.root = world
+            .create_entity()
+            .with(self.ui.clone().expect("UI not loaded"))
+            .with(Removal::new(State::TournamentSetup))
+            .build()
+            .into();
+    }
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        exec_removal(
+            &world.entities(),
+            &world.read_storage(),
+            State::TournamentSetup,
+        );
+        self.root = None;
+        self.names = None;
+        self.format_label = None;
+        self.change_format = None;
+        self.start = None;
+        self.resume = None;
+        self.back = None;
+    }
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        use StateEvent::*;
+        match event {
+            Ui(ui_event) if ui_event.event_type == UiEventType::Click => {
+                if Some(ui_event.target) == self.back {
+                    SimpleTrans::Switch(Box::from(MainMenuState::default()))
+                } else if Some(ui_event.target) == self.change_format {
+                    self.format = self.format.next();
+                    self.show_format(&data);
+                    SimpleTrans::None
+                } else if Some(ui_event.target) == self.start {
+                    match Tournament::new(self.format, self.players(&data)) {
+                        Some(tournament) => {
+                            if let Err(e) = tournament.save(tournament_file()) {
+                                eprintln!("Failed to save the tournament: {}", e);
+                            }
+                            SimpleTrans::Switch(Box::from(BracketState::new(tournament)))
+                        }
+                        None => {
+                            eprintln!("A tournament needs at least two players");
+                            SimpleTrans::None
+                        }
+                    }
+                } else if Some(ui_event.target) == self.resume {
+                    match Tournament::load(tournament_file()) {
+                        Ok(tournament) => {
+                            SimpleTrans::Switch(Box::from(BracketState::new(tournament)))
+                        }
+                        Err(e) => {
+                            eprintln!("No saved tournament to continue: {}", e);
+                            SimpleTrans::None
+                        }
+                    }
+                } else {
+                    SimpleTrans::None
+                }
+            }
+            _ => SimpleTrans::None,
+        }
+    }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if self.names.is_none()
+            || self.format_label.is_none()
+            || self.change_format.is_none()
+            || self.start.is_none()
+            || self.resume.is_none()
+            || self.back.is_none()
+        {
+            self.names = data.world.exec(find_ui(MENU_TXT_NAMES_ID));
+            self.format_label = data.world.exec(find_ui(MENU_LBL_FORMAT_ID));
+            self.change_format = data.world.exec(find_ui(MENU_BTN_FORMAT_ID));
+            self.start = data.world.exec(find_ui(MENU_BTN_START_ID));
+            self.resume = data.world.exec(find_ui(MENU_BTN_RESUME_ID));
+            self.back = data.world.exec(find_ui(MENU_BTN_BACK_ID));
+            self.show_format(data);
+        }
+        SimpleTrans::None
+    }
+}
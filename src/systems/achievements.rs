@@ -0,0 +1,174 @@
+use amethyst::{
+    core::timing::Time,
+    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, World, Write, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use std::collections::VecDeque;
+
+use crate::{
+    achievements::{AchievementProgress, AchievementToast, Achievements, Feat},
+    audio::ScoreSound,
+    effects::{Impact, ImpactKind},
+    pong::{Ai, Paddle, ScoreBoard, Side},
+    rules::{MatchClock, MatchPhase},
+    theme::Theme,
+};
+
+/// Seconds an achievement is announced for.
+const TOAST_DURATION: f32 = 3.0;
+/// Seconds the toast fades out at the end.
+const TOAST_FADE: f32 = 0.5;
+
+/// Unlocks achievements for what the players do in a match and announces them with a toast.
+/// Only feats of players count, the AI can't unlock anything.
+/// Unlocks go into the `AchievementProgress` resource, the game state loads and saves it.
+pub struct AchievementSystem {
+    impacts: ReaderId<Impact>,
+    sounds: ReaderId<ScoreSound>,
+    /// Paddle hits of the current rally.
+    hits: u32,
+    /// Wall bounces since the last paddle hit.
+    wall_bounces: u32,
+    /// Whether the result of the match has been checked.
+    decided: bool,
+    /// Announcements waiting for the current one to finish.
+    toasts: VecDeque<String>,
+    /// Time left of the current announcement.
+    toast_timer: f32,
+}
+
+impl AchievementSystem {
+    pub fn new(world: &mut World) -> Self {
+        let impacts = world
+            .entry::<EventChannel<Impact>>()
+            .or_insert_with(EventChannel::new)
+            .register_reader();
+        let sounds = world
+            .entry::<EventChannel<ScoreSound>>()
+            .or_insert_with(EventChannel::new)
+            .register_reader();
+        AchievementSystem {
+            impacts,
+            sounds,
+            hits: 0,
+            wall_bounces: 0,
+            decided: false,
+            toasts: VecDeque::new(),
+            toast_timer: 0.0,
+        }
+    }
+
+    fn achieve(
+        &mut self,
+        progress: &mut AchievementProgress,
+        achievements: &Achievements,
+        feat: Feat,
+    ) {
+        for achievement in progress.unlock(achievements, feat) {
+            self.toasts
+                .push_back(format!("{}: {}", achievement.name, achievement.description));
+        }
+    }
+}
+
+impl<'s> System<'s> for AchievementSystem {
+    type SystemData = (
+        ReadStorage<'s, Paddle>,
+        ReadStorage<'s, Ai>,
+        WriteStorage<'s, UiText>,
+        Option<ReadExpect<'s, AchievementToast>>,
+        Read<'s, EventChannel<Impact>>,
+        Read<'s, EventChannel<ScoreSound>>,
+        Read<'s, ScoreBoard>,
+        Read<'s, MatchClock>,
+        Read<'s, Achievements>,
+        Write<'s, AchievementProgress>,
+        Read<'s, Theme>,
+        Read<'s, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            paddles,
+            ais,
+            mut texts,
+            toast,
+            impacts,
+            sounds,
+            scores,
+            clock,
+            achievements,
+            mut progress,
+            theme,
+            time,
+        ): Self::SystemData,
+    ) {
+        let players = (&paddles, !&ais)
+            .join()
+            .map(|(paddle, _)| paddle.side)
+            .collect::<Vec<_>>();
+
+        for impact in impacts.read(&mut self.impacts) {
+            match impact.kind {
+                ImpactKind::Paddle => {
+                    self.hits += 1;
+                    self.wall_bounces = 0;
+                    let feat = Feat::PaddleHit { hits: self.hits };
+                    self.achieve(&mut progress, &achievements, feat);
+                }
+                ImpactKind::Wall => self.wall_bounces += 1,
+                ImpactKind::Score => {
+                    // The normal points away from the wall the ball left through.
+                    let scorer = if impact.normal.x > 0.0 {
+                        Side::Right
+                    } else {
+                        Side::Left
+                    };
+                    if players.contains(&scorer) {
+                        let feat = Feat::Score {
+                            spin: impact.spin,
+                            wall_bounces: self.wall_bounces,
+                        };
+                        self.achieve(&mut progress, &achievements, feat);
+                    }
+                    self.hits = 0;
+                    self.wall_bounces = 0;
+                }
+            }
+        }
+        for sound in sounds.read(&mut self.sounds) {
+            self.achieve(&mut progress, &achievements, Feat::Heard(*sound));
+        }
+        if let MatchPhase::Finished(winner) = clock.phase {
+            if !self.decided && players.contains(&winner) {
+                let conceded = match winner {
+                    Side::Left => scores.score_right,
+                    Side::Right => scores.score_left,
+                };
+                self.achieve(&mut progress, &achievements, Feat::Win { conceded });
+            }
+            self.decided = true;
+        }
+
+        // One announcement after the other
+        let toast = match toast.and_then(|toast| texts.get_mut(toast.text)) {
+            Some(toast) => toast,
+            None => return,
+        };
+        self.toast_timer -= time.delta_seconds();
+        if self.toast_timer <= 0.0 {
+            toast.text = match self.toasts.pop_front() {
+                Some(text) => {
+                    self.toast_timer = TOAST_DURATION;
+                    text
+                }
+                None => String::new(),
+            };
+        }
+        let fade = (self.toast_timer / TOAST_FADE).max(0.0).min(1.0);
+        toast.color[3] = theme.text_color[3] * fade;
+    }
+}
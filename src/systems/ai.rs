@@ -27,16 +27,21 @@ impl<'s> System<'s> for AiSystem {
             }
         };
         let ball_transform = ball_transform.clone();
-        for (paddle, transform, _) in (&mut paddles, &mut transforms, &ai).join() {
+        for (paddle, transform, ai) in (&mut paddles, &mut transforms, &ai).join() {
+            let params = &ai.params;
             // AI Part
             let ball_y = ball_transform.translation().y;
             let paddle_y = transform.translation().y;
-            let scaled_acc = clamp(100.0 * (ball_y - paddle_y), -300.0, 300.0);
+            let scaled_acc = clamp(
+                params.gain * (ball_y - paddle_y),
+                -params.max_acceleration,
+                params.max_acceleration,
+            );
 
             // Physics
             let new_speed = paddle.velocity + scaled_acc * time.delta_seconds();
-            paddle.velocity = clamp(new_speed, -150.0, 150.0);
-            paddle.velocity *= 1.0 - 5.0 * time.delta_seconds();
+            paddle.velocity = clamp(new_speed, -params.max_speed, params.max_speed);
+            paddle.velocity *= 1.0 - params.damping * time.delta_seconds();
             let mut paddle_y = transform.translation().y;
             paddle_y += paddle.velocity * time.delta_seconds();
             if paddle_y < TOP_OF_SCREEN {
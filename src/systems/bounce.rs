@@ -5,13 +5,15 @@ use amethyst::{
         math::{Rotation, Vector3},
         Transform,
     },
-    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, WriteStorage},
+    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage},
+    shrev::EventChannel,
 };
 
 use std::ops::Deref;
 
 use crate::{
     audio::{play_bounce_paddle_sound, play_bounce_wall_sound, Sounds},
+    effects::{Impact, ImpactKind},
     pong::{Ball, Paddle, Side, ARENA_HEIGHT, GRIP_WALL, MAX_ROTATION_ON_COLLISION, ROT_FACTOR},
 };
 
@@ -23,13 +25,14 @@ impl<'s> System<'s> for BounceSystem {
         ReadStorage<'s, Paddle>,
         ReadStorage<'s, Transform>,
         Read<'s, AssetStorage<Source>>,
-        ReadExpect<'s, Sounds>,
+        Option<ReadExpect<'s, Sounds>>,
         Option<Read<'s, Output>>,
+        Write<'s, EventChannel<Impact>>,
     );
 
     fn run(
         &mut self,
-        (mut balls, paddles, transforms, storage, sounds, audio_output): Self::SystemData,
+        (mut balls, paddles, transforms, storage, sounds, audio_output, mut impacts): Self::SystemData,
     ) {
         // Check whether a ball collided, and bounce off accordingly.
         // We also check for the velocity of the ball every time,
@@ -50,11 +53,20 @@ impl<'s> System<'s> for BounceSystem {
                 ball.velocity +=
                     sign * GRIP_WALL * Vector3::new(ball.rot_velocity * ball.radius, 0.0, 0.0);
                 ball.rot_velocity *= 1.0 - GRIP_WALL;
-                play_bounce_wall_sound(
-                    &*sounds,
-                    &storage,
-                    audio_output.as_ref().map(|o| o.deref()),
-                );
+                impacts.single_write(Impact {
+                    kind: ImpactKind::Wall,
+                    position: *transform.translation(),
+                    normal: Vector3::new(0.0, sign, 0.0),
+                    speed: ball.velocity.norm(),
+                    spin: ball.rot_velocity,
+                });
+                if let Some(sounds) = &sounds {
+                    play_bounce_wall_sound(
+                        &*sounds,
+                        &storage,
+                        audio_output.as_ref().map(|o| o.deref()),
+                    );
+                }
             }
 
             // Bounce at the paddles.
@@ -81,11 +93,20 @@ impl<'s> System<'s> for BounceSystem {
                         // of the same length as the incoming speed vector
                         ball.velocity = rotation * (ball.velocity.norm() / unit.norm() * unit);
                         ball.velocity *= 1.1;
-                        play_bounce_paddle_sound(
-                            &*sounds,
-                            &storage,
-                            audio_output.as_ref().map(|o| o.deref()),
-                        );
+                        impacts.single_write(Impact {
+                            kind: ImpactKind::Paddle,
+                            position: *transform.translation(),
+                            normal: unit,
+                            speed: ball.velocity.norm(),
+                            spin: ball.rot_velocity,
+                        });
+                        if let Some(sounds) = &sounds {
+                            play_bounce_paddle_sound(
+                                &*sounds,
+                                &storage,
+                                audio_output.as_ref().map(|o| o.deref()),
+                            );
+                        }
                     }
                     None => {}
                 }
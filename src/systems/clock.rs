@@ -0,0 +1,50 @@
+use amethyst::{
+    core::timing::Time,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, ReadExpect, System, SystemData, Write, WriteStorage},
+    ui::UiText,
+};
+
+use crate::{
+    locale::Locale,
+    pong::{Ball, ScoreBoard, ScoreText},
+    rules::MatchClock,
+};
+
+#[derive(SystemDesc)]
+pub struct ClockSystem;
+
+impl<'s> System<'s> for ClockSystem {
+    type SystemData = (
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, UiText>,
+        Write<'s, MatchClock>,
+        Read<'s, ScoreBoard>,
+        Option<ReadExpect<'s, ScoreText>>,
+        Read<'s, Time>,
+        Read<'s, Locale>,
+    );
+
+    fn run(
+        &mut self,
+        (mut balls, mut ui_text, mut clock, scores, score_text, time, locale): Self::SystemData,
+    ) {
+        // The clock only runs while the ball is in play.
+        let in_play = (&balls).join().all(|ball| ball.hidden.is_none());
+        if in_play {
+            let old_factor = clock.ball_speed_factor;
+            if clock.tick(time.delta_seconds(), &scores) {
+                // Overtime started, speed up the ball that's already in play.
+                for ball in (&mut balls).join() {
+                    ball.velocity *= clock.ball_speed_factor / old_factor;
+                }
+            }
+        }
+        if let Some(text) = score_text
+            .as_ref()
+            .and_then(|score_text| ui_text.get_mut(score_text.clock))
+        {
+            text.text = clock.display(&locale);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use amethyst::{
+    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, World, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+    ui::UiText,
+};
+
+use crate::{
+    effects::{Impact, ImpactKind},
+    hud::{CourtText, HudConfig},
+    locale::Locale,
+    pong::{Ball, PlayerNames, ScaledUi, Side},
+};
+
+/// Highest number the serve countdown shows, one per second.
+const COUNTDOWN_FROM: f32 = 3.0;
+/// Distance of the serve arrow from the middle, in reference pixels.
+const SERVE_ARROW_OFFSET: f32 = 40.0;
+
+/// Counts down to the serve, names the player who scored the last point
+/// and points where the ball will be served, while it waits in the middle.
+pub struct CourtHudSystem {
+    reader: ReaderId<Impact>,
+    /// The player who scored the point before the current serve.
+    scorer: Option<Side>,
+}
+
+impl CourtHudSystem {
+    pub fn new(world: &mut World) -> Self {
+        let reader = world
+            .entry::<EventChannel<Impact>>()
+            .or_insert_with(EventChannel::new)
+            .register_reader();
+        CourtHudSystem {
+            reader,
+            scorer: None,
+        }
+    }
+}
+
+impl<'s> System<'s> for CourtHudSystem {
+    type SystemData = (
+        ReadStorage<'s, Ball>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, ScaledUi>,
+        Option<ReadExpect<'s, CourtText>>,
+        Read<'s, PlayerNames>,
+        Read<'s, EventChannel<Impact>>,
+        Read<'s, HudConfig>,
+        Read<'s, Locale>,
+    );
+
+    fn run(
+        &mut self,
+        (balls, mut texts, mut layouts, court_text, names, impacts, config, locale): Self::SystemData,
+    ) {
+        for impact in impacts.read(&mut self.reader) {
+            if impact.kind == ImpactKind::Score {
+                // The normal points away from the wall the ball left through.
+                self.scorer = Some(if impact.normal.x > 0.0 {
+                    Side::Right
+                } else {
+                    Side::Left
+                });
+            }
+        }
+
+        let waiting = (&balls)
+            .join()
+            .find_map(|ball| ball.hidden.map(|timer| (timer, ball.velocity)));
+        let (countdown, banner, arrow, direction) = match waiting {
+            Some((timer, velocity)) => {
+                // Whole seconds, so every number stays up long enough to read.
+                let count = timer.ceil().max(1.0).min(COUNTDOWN_FROM);
+                let banner = self
+                    .scorer
+                    .map(|side| locale.format("hud.scores", &[names.name(side)]))
+                    .unwrap_or_default();
+                let (arrow, direction) = if velocity.x < 0.0 {
+                    ("<<", -1.0)
+                } else {
+                    (">>", 1.0)
+                };
+                (count.to_string(), banner, arrow.to_string(), direction)
+            }
+            None => {
+                self.scorer = None;
+                (String::new(), String::new(), String::new(), 1.0)
+            }
+        };
+
+        let court_text = match court_text {
+            Some(court_text) => court_text,
+            None => return,
+        };
+        if let Some(text) = texts.get_mut(court_text.countdown) {
+            text.text = if config.serve_countdown {
+                countdown
+            } else {
+                String::new()
+            };
+        }
+        if let Some(text) = texts.get_mut(court_text.banner) {
+            text.text = if config.score_banner {
+                banner
+            } else {
+                String::new()
+            };
+        }
+        if let Some(text) = texts.get_mut(court_text.serve_arrow) {
+            text.text = if config.serve_arrow {
+                arrow
+            } else {
+                String::new()
+            };
+        }
+        if let Some(layout) = layouts.get_mut(court_text.serve_arrow) {
+            layout.x = direction * SERVE_ARROW_OFFSET;
+        }
+    }
+}
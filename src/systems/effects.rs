@@ -0,0 +1,195 @@
+use amethyst::{
+    core::{
+        math::{Rotation, Vector3},
+        timing::Time,
+        Hidden, Transform,
+    },
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, World, WriteStorage},
+    renderer::{palette::Srgba, resources::Tint},
+    shrev::{EventChannel, ReaderId},
+};
+use rand::Rng;
+
+use std::{collections::VecDeque, f32::consts::FRAC_PI_2};
+
+use crate::{
+    effects::{EffectsConfig, Impact, ImpactKind, Particle, TrailSegment, TRAIL_SEGMENTS},
+    pong::{Ball, BALL_RADIUS, BALL_TEXTURE_SIZE},
+};
+
+/// Seconds between two recorded positions of the trail.
+const TRAIL_INTERVAL: f32 = 0.02;
+/// Ball speed needed for each visible trail segment.
+const TRAIL_SPEED_PER_SEGMENT: f32 = 15.0;
+/// Opacity of the trail segment closest to the ball.
+const TRAIL_ALPHA: f32 = 0.4;
+/// Scale of the ball sprite for a spark.
+const SPARK_SCALE: f32 = 0.3 * 2.0 * BALL_RADIUS / BALL_TEXTURE_SIZE[0];
+/// Fraction of their velocity sparks lose per second.
+const SPARK_DRAG: f32 = 3.0;
+
+/// Moves the trail after the ball and lets sparks fly from impacts.
+pub struct EffectsSystem {
+    reader: ReaderId<Impact>,
+    /// Recent positions of the ball, newest first.
+    trail: VecDeque<Vector3<f32>>,
+    /// Time since the last recorded trail position.
+    since_sample: f32,
+}
+
+impl EffectsSystem {
+    pub fn new(world: &mut World) -> Self {
+        let reader = world
+            .entry::<EventChannel<Impact>>()
+            .or_insert_with(EventChannel::new)
+            .register_reader();
+        EffectsSystem {
+            reader,
+            trail: VecDeque::with_capacity(TRAIL_SEGMENTS + 1),
+            since_sample: 0.0,
+        }
+    }
+}
+
+impl<'s> System<'s> for EffectsSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, TrailSegment>,
+        WriteStorage<'s, Particle>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, EventChannel<Impact>>,
+        Read<'s, Time>,
+        Read<'s, EffectsConfig>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            balls,
+            segments,
+            mut particles,
+            mut transforms,
+            mut tints,
+            mut hidden,
+            impacts,
+            time,
+            config,
+        ): Self::SystemData,
+    ) {
+        let delta = time.delta_seconds();
+
+        // Trail: record where the ball flies and lay the segments along its path.
+        let ball = (&balls, &transforms)
+            .join()
+            .find(|(ball, _)| ball.hidden.is_none())
+            .map(|(ball, transform)| {
+                (
+                    ball.velocity.norm(),
+                    *transform.translation(),
+                    *transform.scale(),
+                )
+            });
+        match ball {
+            Some((speed, position, scale)) if config.trail => {
+                self.since_sample += delta;
+                if self.trail.is_empty() || self.since_sample >= TRAIL_INTERVAL {
+                    self.since_sample = 0.0;
+                    self.trail.push_front(position);
+                    self.trail.truncate(TRAIL_SEGMENTS + 1);
+                }
+                let visible = ((speed / TRAIL_SPEED_PER_SEGMENT) as usize).min(TRAIL_SEGMENTS);
+                for (entity, segment, transform, tint) in
+                    (&entities, &segments, &mut transforms, &mut tints).join()
+                {
+                    // The newest position is covered by the ball itself.
+                    match self.trail.get(segment.index + 1) {
+                        Some(sample) if segment.index < visible => {
+                            let fade = 1.0 - segment.index as f32 / visible as f32;
+                            transform.set_translation_xyz(sample.x, sample.y, -0.1);
+                            transform.set_scale(scale * (0.5 + 0.5 * fade));
+                            tint.0.alpha = TRAIL_ALPHA * fade;
+                            hidden.remove(entity);
+                        }
+                        _ => {
+                            let _ = hidden.insert(entity, Hidden);
+                        }
+                    }
+                }
+            }
+            _ => {
+                // The ball waits for the serve, the trail starts over.
+                self.trail.clear();
+                for (entity, _) in (&entities, &segments).join() {
+                    let _ = hidden.insert(entity, Hidden);
+                }
+            }
+        }
+
+        // Sparks: age the flying ones, hide them once they burnt out.
+        let mut burnt_out = Vec::new();
+        for (entity, particle, transform, tint, _) in (
+            &entities,
+            &mut particles,
+            &mut transforms,
+            &mut tints,
+            !&hidden,
+        )
+            .join()
+        {
+            particle.age += delta;
+            if particle.age >= particle.lifetime {
+                burnt_out.push(entity);
+                continue;
+            }
+            transform.prepend_translation(particle.velocity * delta);
+            particle.velocity *= (1.0 - SPARK_DRAG * delta).max(0.0);
+            tint.0.alpha = 1.0 - particle.age / particle.lifetime;
+        }
+        for entity in burnt_out {
+            let _ = hidden.insert(entity, Hidden);
+        }
+
+        // Launch new sparks from the pool, if there are enough left.
+        let mut launched = Vec::new();
+        let mut rng = rand::thread_rng();
+        for impact in impacts.read(&mut self.reader) {
+            let (count, spread, speed, color) = match impact.kind {
+                ImpactKind::Wall if config.sparks => (6, 1.2, 30.0, [0.8, 0.8, 1.0]),
+                ImpactKind::Paddle if config.sparks => (10, 1.0, 45.0, [1.0, 0.9, 0.4]),
+                ImpactKind::Score if config.bursts => (24, FRAC_PI_2, 60.0, [1.0, 0.2, 0.1]),
+                _ => continue,
+            };
+            let free = (&entities, &particles, &hidden)
+                .join()
+                .map(|(entity, _, _)| entity)
+                .filter(|entity| !launched.contains(entity))
+                .take(count)
+                .collect::<Vec<Entity>>();
+            for entity in free {
+                let angle = rng.gen_range(-spread, spread);
+                let rotation = Rotation::from_axis_angle(&Vector3::z_axis(), angle);
+                if let Some(particle) = particles.get_mut(entity) {
+                    particle.velocity = rotation * impact.normal * speed * rng.gen_range(0.3, 1.0);
+                    particle.age = 0.0;
+                    particle.lifetime = rng.gen_range(0.25, 0.5);
+                }
+                if let Some(transform) = transforms.get_mut(entity) {
+                    transform.set_translation_xyz(impact.position.x, impact.position.y, 0.1);
+                    transform.set_scale(Vector3::new(SPARK_SCALE, SPARK_SCALE, 1.0));
+                }
+                if let Some(tint) = tints.get_mut(entity) {
+                    let [red, green, blue] = color;
+                    tint.0 = Srgba::new(red, green, blue, 1.0);
+                }
+                launched.push(entity);
+            }
+        }
+        for entity in launched {
+            hidden.remove(entity);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use amethyst::{
+    core::{timing::Time, Transform},
+    ecs::prelude::{Join, Read, System, World, Write, WriteStorage},
+    shrev::{EventChannel, ReaderId},
+};
+use rand::Rng;
+
+use crate::{
+    accessibility::AccessibilityConfig,
+    effects::{CameraShake, EffectsConfig, Impact, ImpactKind},
+    pong::PausedOrRunning,
+};
+
+/// Paddle hits slower than this don't shake the camera.
+const HARD_HIT_SPEED: f32 = 90.0;
+/// Paddle hits at this speed or faster shake the camera the most.
+const HARDEST_HIT_SPEED: f32 = 200.0;
+/// Trauma added by the hardest paddle hit.
+const PADDLE_TRAUMA: f32 = 0.5;
+/// Trauma added by scoring.
+const SCORE_TRAUMA: f32 = 0.8;
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 2.5;
+/// Largest offset of the camera in arena units, at full trauma.
+const MAX_SHAKE_OFFSET: f32 = 2.0;
+/// Length of the freeze after a paddle hit in seconds.
+const HIT_STOP_DURATION: f32 = 0.05;
+
+/// Shakes the camera on hard impacts and freezes the gameplay on paddle hits.
+/// This isn't pausable, so it keeps running during the hit-stop it starts.
+pub struct JuiceSystem {
+    reader: ReaderId<Impact>,
+    /// Time left of the current hit-stop.
+    hit_stop: f32,
+}
+
+impl JuiceSystem {
+    pub fn new(world: &mut World) -> Self {
+        let reader = world
+            .entry::<EventChannel<Impact>>()
+            .or_insert_with(EventChannel::new)
+            .register_reader();
+        JuiceSystem {
+            reader,
+            hit_stop: 0.0,
+        }
+    }
+}
+
+impl<'s> System<'s> for JuiceSystem {
+    type SystemData = (
+        WriteStorage<'s, CameraShake>,
+        WriteStorage<'s, Transform>,
+        Write<'s, PausedOrRunning>,
+        Read<'s, EventChannel<Impact>>,
+        Read<'s, Time>,
+        Read<'s, EffectsConfig>,
+        Read<'s, AccessibilityConfig>,
+    );
+
+    fn run(
+        &mut self,
+        (mut shakes, mut transforms, mut flow, impacts, time, config, accessibility): Self::SystemData,
+    ) {
+        if *flow == PausedOrRunning::Paused {
+            // Don't miss impacts from before the pause, but don't act on them either.
+            impacts.read(&mut self.reader).for_each(drop);
+            return;
+        }
+        let delta = time.delta_seconds();
+        let shake = config.screen_shake && !accessibility.reduce_motion;
+
+        let mut trauma = 0.0;
+        for impact in impacts.read(&mut self.reader) {
+            match impact.kind {
+                ImpactKind::Paddle => {
+                    let hardness =
+                        (impact.speed - HARD_HIT_SPEED) / (HARDEST_HIT_SPEED - HARD_HIT_SPEED);
+                    trauma += PADDLE_TRAUMA * hardness.max(0.0).min(1.0);
+                    if config.hit_stop {
+                        self.hit_stop = HIT_STOP_DURATION;
+                    }
+                }
+                ImpactKind::Score => trauma += SCORE_TRAUMA,
+                ImpactKind::Wall => {}
+            }
+        }
+
+        // Hit-stop: freeze the pausable systems, until the time is up.
+        if self.hit_stop > 0.0 {
+            self.hit_stop -= delta;
+            if self.hit_stop > 0.0 && *flow == PausedOrRunning::Running {
+                *flow = PausedOrRunning::HitStop;
+            } else if self.hit_stop <= 0.0 && *flow == PausedOrRunning::HitStop {
+                *flow = PausedOrRunning::Running;
+            }
+        }
+
+        let mut rng = rand::thread_rng();
+        for (camera, transform) in (&mut shakes, &mut transforms).join() {
+            camera.trauma = if shake {
+                (camera.trauma + trauma - TRAUMA_DECAY * delta)
+                    .max(0.0)
+                    .min(1.0)
+            } else {
+                0.0
+            };
+            // Squared trauma keeps small shakes subtle.
+            let offset = MAX_SHAKE_OFFSET * camera.trauma * camera.trauma;
+            transform.set_translation_xyz(
+                camera.rest.x + offset * rng.gen_range(-1.0, 1.0),
+                camera.rest.y + offset * rng.gen_range(-1.0, 1.0),
+                camera.rest.z,
+            );
+        }
+    }
+}
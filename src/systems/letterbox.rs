@@ -0,0 +1,59 @@
+use amethyst::{
+    ecs::prelude::{Join, ReadExpect, ReadStorage, System, WriteStorage},
+    renderer::Camera,
+    ui::{UiText, UiTransform},
+    window::ScreenDimensions,
+};
+
+use crate::pong::{
+    arena_view, ArenaCamera, ScaledUi, ARENA_HEIGHT, ARENA_WIDTH, HUD_REFERENCE_HEIGHT,
+};
+
+/// Fits the arena cameras to the window, keeping the aspect ratio of the arena,
+/// and scales the HUD with the height of the arena on screen.
+/// The HUD is anchored to the edges of the arena, not to the bars around it.
+/// Runs in every state, it has nothing to do without an arena camera or HUD.
+#[derive(Default)]
+pub struct LetterboxSystem;
+
+impl<'s> System<'s> for LetterboxSystem {
+    type SystemData = (
+        ReadExpect<'s, ScreenDimensions>,
+        ReadStorage<'s, ArenaCamera>,
+        WriteStorage<'s, Camera>,
+        ReadStorage<'s, ScaledUi>,
+        WriteStorage<'s, UiTransform>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(
+        &mut self,
+        (screen, arena_cameras, mut cameras, layouts, mut transforms, mut texts): Self::SystemData,
+    ) {
+        let (width, height) = (screen.width(), screen.height());
+        if width <= 0.0 || height <= 0.0 {
+            // Minimized
+            return;
+        }
+        let (view_width, view_height) = arena_view(width / height);
+        for (_, camera) in (&arena_cameras, &mut cameras).join() {
+            *camera = Camera::standard_2d(view_width, view_height);
+        }
+
+        let scale = height * ARENA_HEIGHT / view_height / HUD_REFERENCE_HEIGHT;
+        // Size of a single bar in pixels, on one side of the arena
+        let bar_width = (width - ARENA_WIDTH * height / view_height) / 2.0;
+        let bar_height = (height - ARENA_HEIGHT * height / view_height) / 2.0;
+        for (layout, transform) in (&layouts, &mut transforms).join() {
+            // Anchors at the edges are moved inwards by the bar on their side
+            let (anchor_x, anchor_y) = transform.anchor.norm_offset();
+            transform.local_x = layout.x * scale - 2.0 * anchor_x * bar_width;
+            transform.local_y = layout.y * scale - 2.0 * anchor_y * bar_height;
+            transform.width = layout.width * scale;
+            transform.height = layout.height * scale;
+        }
+        for (layout, text) in (&layouts, &mut texts).join() {
+            text.font_size = layout.font_size * scale;
+        }
+    }
+}
@@ -1,12 +1,28 @@
 //! All systems running for the game.
+mod achievements;
 mod ai;
 mod bounce;
+mod clock;
+mod court_hud;
+mod effects;
+mod juice;
+mod letterbox;
 mod move_balls;
 mod paddle;
+mod practice;
+mod spin_hud;
 mod winner;
 
+pub use self::achievements::AchievementSystem;
 pub use self::ai::AiSystem;
 pub use self::bounce::BounceSystem;
+pub use self::clock::ClockSystem;
+pub use self::court_hud::CourtHudSystem;
+pub use self::effects::EffectsSystem;
+pub use self::juice::JuiceSystem;
+pub use self::letterbox::LetterboxSystem;
 pub use self::move_balls::MoveBallsSystem;
 pub use self::paddle::PaddleSystem;
+pub use self::practice::PracticeSystem;
+pub use self::spin_hud::SpinHudSystem;
 pub use self::winner::WinnerSystem;
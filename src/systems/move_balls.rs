@@ -1,12 +1,11 @@
 use amethyst::{
-    core::math::{Rotation, Vector3},
     core::timing::Time,
     core::transform::Transform,
     derive::SystemDesc,
     ecs::prelude::{Join, Read, System, SystemData, WriteStorage},
 };
 
-use crate::pong::{Ball, SPEED_ROT_FACTOR};
+use crate::pong::{curve, Ball};
 
 #[derive(SystemDesc)]
 pub struct MoveBallsSystem;
@@ -29,11 +28,7 @@ impl<'s> System<'s> for MoveBallsSystem {
                     *timer -= time.delta_seconds();
                 }
             } else {
-                let speed_rot = Rotation::from_axis_angle(
-                    &Vector3::z_axis(),
-                    SPEED_ROT_FACTOR * ball.rot_velocity * time.delta_seconds(),
-                );
-                ball.velocity = speed_rot * ball.velocity;
+                ball.velocity = curve(ball.velocity, ball.rot_velocity, time.delta_seconds());
                 local.prepend_translation(ball.velocity * time.delta_seconds());
                 local.prepend_rotation_z_axis(ball.rot_velocity * time.delta_seconds());
             }
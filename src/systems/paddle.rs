@@ -5,7 +5,10 @@ use amethyst::{
     input::{InputHandler, StringBindings},
 };
 
-use crate::pong::{Ai, Paddle, Side, BOTTOM_OF_SCREEN, TOP_OF_SCREEN};
+use crate::{
+    pong::{Ai, Paddle, Side, BOTTOM_OF_SCREEN, TOP_OF_SCREEN},
+    replay::Frame,
+};
 
 #[derive(SystemDesc)]
 pub struct PaddleSystem;
@@ -17,13 +20,16 @@ impl<'s> System<'s> for PaddleSystem {
         ReadStorage<'s, Ai>,
         Read<'s, InputHandler<StringBindings>>,
         Read<'s, Time>,
+        Option<Read<'s, Frame>>,
     );
 
-    fn run(&mut self, (mut transforms, mut paddles, ais, input, time): Self::SystemData) {
+    fn run(&mut self, (mut transforms, mut paddles, ais, input, time, replay): Self::SystemData) {
         for (paddle, _, transform) in (&mut paddles, !&ais, &mut transforms).join() {
-            let acceleration = match paddle.side {
-                Side::Left => input.axis_value("left_paddle"),
-                Side::Right => input.axis_value("right_paddle"),
+            // A replay that is played back moves the paddles instead of the players
+            let acceleration = match (&replay, paddle.side) {
+                (Some(frame), side) => frame.axis(side),
+                (None, Side::Left) => input.axis_value("left_paddle"),
+                (None, Side::Right) => input.axis_value("right_paddle"),
             };
             if let Some(acc) = acceleration {
                 let scaled_acc = 2000.0 * acc;
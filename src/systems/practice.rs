@@ -0,0 +1,59 @@
+use amethyst::{
+    core::transform::Transform,
+    derive::SystemDesc,
+    ecs::prelude::{Join, Read, ReadExpect, System, SystemData, Write, WriteExpect, WriteStorage},
+    ui::UiText,
+};
+
+use crate::{
+    locale::Locale,
+    pong::{Ball, GameRng, ARENA_WIDTH},
+    practice::{Opponent, Practice, PracticeText},
+};
+
+/// Replaces the `WinnerSystem` in practice mode.
+/// Counts returns and misses and launches new balls.
+#[derive(SystemDesc)]
+pub struct PracticeSystem;
+
+impl<'s> System<'s> for PracticeSystem {
+    type SystemData = (
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, UiText>,
+        WriteExpect<'s, Practice>,
+        ReadExpect<'s, PracticeText>,
+        Write<'s, GameRng>,
+        Read<'s, Locale>,
+    );
+
+    fn run(
+        &mut self,
+        (mut balls, mut locals, mut ui_text, mut practice, practice_text, mut rng, locale): Self::SystemData,
+    ) {
+        for (ball, transform) in (&mut balls, &mut locals).join() {
+            if ball.hidden.is_some() {
+                continue;
+            }
+            let ball_x = transform.translation().x;
+            let ball_y = transform.translation().y;
+
+            if ball_x <= ball.radius {
+                // The player missed.
+                practice.on_miss();
+                practice.launch(&mut rng.0, ball, transform);
+            } else if ball_x >= ARENA_WIDTH - ball.radius && ball.velocity.x > 0.0 {
+                // The player returned the ball to the right wall.
+                practice.on_return(ball_y);
+                match practice.config.opponent {
+                    Opponent::Wall => ball.velocity.x = -ball.velocity.x,
+                    Opponent::Launcher => practice.launch(&mut rng.0, ball, transform),
+                }
+            }
+        }
+
+        if let Some(text) = ui_text.get_mut(practice_text.status) {
+            text.text = practice.status(&locale);
+        }
+    }
+}
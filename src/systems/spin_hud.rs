@@ -0,0 +1,158 @@
+use amethyst::{
+    core::{math::Vector3, Hidden, Transform},
+    ecs::prelude::{Entities, Join, Read, ReadStorage, System, WriteStorage},
+    renderer::{palette::Srgba, resources::Tint},
+    ui::UiText,
+};
+
+use crate::{
+    hud::{HudConfig, PathDot, SpinMarker, SpinMeter, SPIN_MARKERS},
+    locale::Locale,
+    pong::{predict_path, Ball, BALL_TEXTURE_SIZE},
+};
+
+/// Spin at which the meter and the markers show the most.
+const SPIN_RANGE: f32 = 50.0;
+/// Spin below this isn't shown at all.
+const MIN_VISIBLE_SPIN: f32 = 1.0;
+/// Number of symbols of the meter at full spin.
+const METER_LENGTH: usize = 5;
+/// Distance of the spin markers from the center of the ball.
+const MARKER_ORBIT: f32 = 2.4;
+/// Angle between two spin markers in radians.
+const MARKER_SPACING: f32 = 0.5;
+/// Scale of the ball sprite for spin markers and preview dots.
+const MARKER_SCALE: f32 = 1.0 / BALL_TEXTURE_SIZE[0];
+/// Seconds of flight between two dots of the curve preview.
+const PATH_INTERVAL: f32 = 0.08;
+/// Simulated frames between two dots of the curve preview.
+const PATH_SUBSTEPS: usize = 5;
+/// Colour of the spin display, for counterclockwise and clockwise spin.
+const COUNTERCLOCKWISE_COLOR: [f32; 3] = [0.3, 0.9, 1.0];
+const CLOCKWISE_COLOR: [f32; 3] = [1.0, 0.6, 0.2];
+
+/// Shows the spin of the ball: a meter, markers circling the ball and,
+/// in practice mode, a preview of the curve it's going to fly.
+#[derive(Default)]
+pub struct SpinHudSystem;
+
+impl<'s> System<'s> for SpinHudSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Ball>,
+        ReadStorage<'s, SpinMeter>,
+        ReadStorage<'s, SpinMarker>,
+        ReadStorage<'s, PathDot>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Tint>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, UiText>,
+        Read<'s, HudConfig>,
+        Read<'s, Locale>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            balls,
+            meters,
+            markers,
+            dots,
+            mut transforms,
+            mut tints,
+            mut hidden,
+            mut texts,
+            config,
+            locale,
+        ): Self::SystemData,
+    ) {
+        let ball = (&balls, &transforms)
+            .join()
+            .find(|(ball, _)| ball.hidden.is_none())
+            .map(|(ball, transform)| {
+                (
+                    ball.rot_velocity,
+                    ball.velocity,
+                    *transform.translation(),
+                    transform.rotation().euler_angles().2,
+                )
+            });
+        let spin = ball.map_or(0.0, |(spin, ..)| spin);
+        let strength = (spin.abs() / SPIN_RANGE).min(1.0);
+        let visible = spin.abs() >= MIN_VISIBLE_SPIN;
+        let [red, green, blue] = if spin > 0.0 {
+            COUNTERCLOCKWISE_COLOR
+        } else {
+            CLOCKWISE_COLOR
+        };
+
+        // Meter: which way the ball bends on screen, and how hard.
+        for (_, text) in (&meters, &mut texts).join() {
+            text.text = match ball {
+                Some((spin, velocity, ..)) if visible && config.spin_meter => {
+                    // Counterclockwise spin turns the velocity to the left.
+                    let symbol = if spin * velocity.x > 0.0 { "^" } else { "v" };
+                    let length = ((strength * METER_LENGTH as f32).ceil() as usize).max(1);
+                    locale.format("hud.curve", &[&symbol.repeat(length)])
+                }
+                _ => String::new(),
+            };
+            text.color = [red, green, blue, text.color[3]];
+        }
+
+        // Markers: circle the ball, trailing the leading one against the spin.
+        let shown = ((strength * SPIN_MARKERS as f32).ceil() as usize).max(1);
+        for (entity, marker, transform, tint) in
+            (&entities, &markers, &mut transforms, &mut tints).join()
+        {
+            match ball {
+                Some((_, _, position, rotation))
+                    if visible && config.spin_overlay && marker.index < shown =>
+                {
+                    let angle = rotation - spin.signum() * MARKER_SPACING * marker.index as f32;
+                    transform.set_translation_xyz(
+                        position.x + MARKER_ORBIT * angle.cos(),
+                        position.y + MARKER_ORBIT * angle.sin(),
+                        0.05,
+                    );
+                    transform.set_scale(Vector3::new(MARKER_SCALE, MARKER_SCALE, 1.0));
+                    let fade = 1.0 - marker.index as f32 / SPIN_MARKERS as f32;
+                    tint.0 = Srgba::new(red, green, blue, (0.3 + 0.7 * strength) * fade);
+                    hidden.remove(entity);
+                }
+                _ => {
+                    let _ = hidden.insert(entity, Hidden);
+                }
+            }
+        }
+
+        // Curve preview: dots along the flight until the ball leaves the arena.
+        let count = dots.join().count();
+        let path = match ball {
+            Some((spin, velocity, position, _)) if config.curve_preview => predict_path(
+                position,
+                velocity,
+                spin,
+                PATH_INTERVAL / PATH_SUBSTEPS as f32,
+                count * PATH_SUBSTEPS,
+            ),
+            _ => Vec::new(),
+        };
+        for (entity, dot, transform, tint) in (&entities, &dots, &mut transforms, &mut tints).join()
+        {
+            match path.get((dot.index + 1) * PATH_SUBSTEPS - 1) {
+                Some(point) => {
+                    transform.set_translation_xyz(point.x, point.y, -0.2);
+                    transform.set_scale(Vector3::new(MARKER_SCALE, MARKER_SCALE, 1.0));
+                    let fade = 1.0 - dot.index as f32 / count as f32;
+                    tint.0 = Srgba::new(1.0, 1.0, 1.0, 0.5 * fade);
+                    hidden.remove(entity);
+                }
+                None => {
+                    let _ = hidden.insert(entity, Hidden);
+                }
+            }
+        }
+    }
+}
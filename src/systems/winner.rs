@@ -5,15 +5,19 @@ use amethyst::{
     core::transform::Transform,
     derive::SystemDesc,
     ecs::prelude::{Join, Read, ReadExpect, System, SystemData, Write, WriteStorage},
+    shrev::EventChannel,
     ui::UiText,
 };
 
 use std::ops::Deref;
 
-use crate::audio::{play_score_sound, Sounds};
+use crate::audio::{play_score_sound, ScoreSound, Sounds};
+use crate::effects::{Impact, ImpactKind};
 use crate::pong::{
-    random_45_vec, Ball, ScoreBoard, ScoreText, ARENA_HEIGHT, ARENA_WIDTH, INITIAL_BALL_SPEED,
+    random_45_vec, Ball, GameRng, ScoreBoard, ScoreText, ARENA_HEIGHT, ARENA_WIDTH,
+    INITIAL_BALL_SPEED,
 };
+use crate::rules::MatchClock;
 
 #[derive(SystemDesc)]
 pub struct WinnerSystem;
@@ -24,15 +28,32 @@ impl<'s> System<'s> for WinnerSystem {
         WriteStorage<'s, Transform>,
         WriteStorage<'s, UiText>,
         Write<'s, ScoreBoard>,
-        ReadExpect<'s, ScoreText>,
+        Write<'s, MatchClock>,
+        Write<'s, GameRng>,
+        Option<ReadExpect<'s, ScoreText>>,
         Read<'s, AssetStorage<Source>>,
-        ReadExpect<'s, Sounds>,
+        Option<ReadExpect<'s, Sounds>>,
         Option<Read<'s, Output>>,
+        Write<'s, EventChannel<Impact>>,
+        Write<'s, EventChannel<ScoreSound>>,
     );
 
     fn run(
         &mut self,
-        (mut balls, mut locals, mut ui_text, mut scores, score_text, storage, sounds, audio_output): Self::SystemData,
+        (
+            mut balls,
+            mut locals,
+            mut ui_text,
+            mut scores,
+            mut clock,
+            mut rng,
+            score_text,
+            storage,
+            sounds,
+            audio_output,
+            mut impacts,
+            mut score_sounds,
+        ): Self::SystemData,
     ) {
         for (ball, transform) in (&mut balls, &mut locals).join() {
             let ball_x = transform.translation().x;
@@ -42,7 +63,10 @@ impl<'s> System<'s> for WinnerSystem {
                 // We top the score at 999 to avoid text overlap.
                 scores.score_right = (scores.score_right + 1).min(999);
 
-                if let Some(text) = ui_text.get_mut(score_text.p2_score) {
+                if let Some(text) = score_text
+                    .as_ref()
+                    .and_then(|score_text| ui_text.get_mut(score_text.p2_score))
+                {
                     text.text = scores.score_right.to_string();
                 }
                 true
@@ -50,7 +74,10 @@ impl<'s> System<'s> for WinnerSystem {
                 // Left player scored on the right side.
                 // We top the score at 999 to avoid text overlap.
                 scores.score_left = (scores.score_left + 1).min(999);
-                if let Some(text) = ui_text.get_mut(score_text.p1_score) {
+                if let Some(text) = score_text
+                    .as_ref()
+                    .and_then(|score_text| ui_text.get_mut(score_text.p1_score))
+                {
                     text.text = scores.score_left.to_string();
                 }
                 true
@@ -64,16 +91,29 @@ impl<'s> System<'s> for WinnerSystem {
                 } else {
                     -Vector3::x_axis()
                 };
-                ball.velocity = random_45_vec(&base_speed, INITIAL_BALL_SPEED); // Reverse Direction
+                impacts.single_write(Impact {
+                    kind: ImpactKind::Score,
+                    position: *transform.translation(),
+                    normal: base_speed.into_inner(),
+                    speed: ball.velocity.norm(),
+                    spin: ball.rot_velocity,
+                });
+                ball.velocity = random_45_vec(
+                    &mut rng.0,
+                    &base_speed,
+                    INITIAL_BALL_SPEED * clock.ball_speed_factor,
+                ); // Reverse Direction
                 ball.rot_velocity = 0.0;
                 ball.hidden = Some(1.0);
                 transform.set_translation_x(ARENA_WIDTH / 2.0); // Reset Position
                 transform.set_translation_y(ARENA_HEIGHT / 2.0); // Reset Position
-                println!(
-                    "Score: | {:^3} | {:^3} |",
-                    scores.score_left, scores.score_right
-                );
-                play_score_sound(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+                clock.on_score(&scores);
+                if let Some(sounds) = &sounds {
+                    let output = audio_output.as_ref().map(|o| o.deref());
+                    if let Some(sound) = play_score_sound(&*sounds, &storage, output) {
+                        score_sounds.single_write(sound);
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,251 @@
+//! Visual themes: the sprites, colours and fonts the game is drawn with.
+//!
+//! Themes live in `assets/themes/<name>.ron`, the one in use is picked in `config/appearance.ron`.
+use amethyst::{
+    assets::{Directory, Source},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+use std::path::Path;
+
+use crate::{
+    config,
+    error::StartupError,
+    locale::{Locale, FALLBACK_LANGUAGE},
+    pong::Side,
+};
+
+/// Names of the theme and the language in use, loaded from `config/appearance.ron`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppearanceConfig {
+    /// File name of the theme in `assets/themes`, without the `.ron` extension.
+    pub theme: String,
+    /// File name of the string table in `assets/locale`, without the `.ron` extension.
+    pub language: String,
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        AppearanceConfig {
+            theme: "neon".to_string(),
+            language: FALLBACK_LANGUAGE.to_string(),
+        }
+    }
+}
+
+/// A visual theme. The default is the look of the game before there were themes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    /// Image of the sprite sheet, relative to the assets directory.
+    pub texture: String,
+    /// Sprite sheet definition for the `texture`, relative to the assets directory.
+    pub sprite_sheet: String,
+    pub sprites: Sprites,
+    /// Colour the window is cleared with, as (red, green, blue, alpha).
+    pub background: [f32; 4],
+    /// Colour of the bars around the arena, if the window has another aspect ratio.
+    pub letterbox: [f32; 4],
+    /// Font of the scores and other text drawn over the arena, unless the language has its own.
+    pub score_font: String,
+    pub score_color: [f32; 4],
+    /// Colour of the match clock and the practice status.
+    pub text_color: [f32; 4],
+    /// Line dividing the two halves of the arena, if any.
+    pub center_line: Option<CenterLine>,
+    /// Images and text colours of the menu buttons.
+    pub buttons: ButtonStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            texture: "texture/sprites.png".to_string(),
+            sprite_sheet: "texture/sprites.ron".to_string(),
+            sprites: Sprites {
+                left_paddle: 0,
+                right_paddle: 0,
+                ball: 1,
+                solid: 5,
+            },
+            background: [0.04, 0.04, 0.04, 1.0],
+            letterbox: [0.0, 0.0, 0.0, 1.0],
+            score_font: "font/square.ttf".to_string(),
+            score_color: [1.0, 0.0, 0.0, 0.2],
+            text_color: [1.0, 0.0, 0.0, 0.4],
+            center_line: None,
+            buttons: ButtonStyle {
+                normal_image: "ui/grey_button14.png".to_string(),
+                hover_image: "ui/red_button13.png".to_string(),
+                press_image: "ui/red_button13.png".to_string(),
+                normal_text_color: [0.1, 0.1, 0.1, 1.0],
+                hover_text_color: [0.9, 0.9, 0.9, 1.0],
+                press_text_color: [0.9, 0.9, 0.9, 1.0],
+            },
+        }
+    }
+}
+
+impl Theme {
+    /// Load the theme `name` from the `themes` directory of the assets.
+    pub fn load(assets_dir: &Path, name: &str) -> Result<Self, StartupError> {
+        let path = assets_dir.join("themes").join(format!("{}.ron", name));
+        config::load_file(
+            &path,
+            "a theme with texture, sprite_sheet, sprites, colours and buttons",
+        )
+    }
+}
+
+/// Indices of the sprites on the theme's sprite sheet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sprites {
+    pub left_paddle: usize,
+    pub right_paddle: usize,
+    pub ball: usize,
+    /// A plain white sprite, that is tinted for the letterbox bars.
+    pub solid: usize,
+}
+
+impl Sprites {
+    pub fn paddle(&self, side: Side) -> usize {
+        match side {
+            Side::Left => self.left_paddle,
+            Side::Right => self.right_paddle,
+        }
+    }
+}
+
+/// Dashes down the middle of the arena, drawn with a sprite of the sprite sheet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CenterLine {
+    pub sprite: usize,
+    /// Number of dashes, spread evenly from the bottom to the top. One draws a solid line.
+    pub dashes: u32,
+    /// Scale of the sprite for each dash.
+    pub scale: [f32; 2],
+    /// Rotation of each dash in degrees, e.g. to stand a lying sprite upright.
+    pub rotation: f32,
+    /// Colour multiplied with the sprite.
+    pub tint: [f32; 4],
+}
+
+/// Looks of the buttons in the UI prefabs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ButtonStyle {
+    pub normal_image: String,
+    pub hover_image: String,
+    pub press_image: String,
+    pub normal_text_color: [f32; 4],
+    pub hover_text_color: [f32; 4],
+    pub press_text_color: [f32; 4],
+}
+
+impl ButtonStyle {
+    /// Fill in the button placeholders of a UI prefab with this style,
+    /// e.g. `normal_text_color: "@button.normal_text_color"`.
+    pub fn restyle(&self, prefab: &str) -> String {
+        let color = |[red, green, blue, alpha]: [f32; 4]| {
+            format!("({:?}, {:?}, {:?}, {:?})", red, green, blue, alpha)
+        };
+        fill_placeholders(prefab, |key| {
+            let value = match key {
+                "button.normal_image" => format!("{:?}", self.normal_image),
+                "button.hover_image" => format!("{:?}", self.hover_image),
+                "button.press_image" => format!("{:?}", self.press_image),
+                "button.normal_text_color" => color(self.normal_text_color),
+                "button.hover_text_color" => color(self.hover_text_color),
+                "button.press_text_color" => color(self.press_text_color),
+                _ => return None,
+            };
+            Some(value)
+        })
+    }
+}
+
+/// The assets directory, serving the UI prefabs restyled with the theme's buttons
+/// and translated to the language in use. Replaces the `Loader`'s default source.
+pub struct ThemedSource {
+    assets: Directory,
+    buttons: ButtonStyle,
+    locale: Locale,
+}
+
+impl ThemedSource {
+    pub fn new(assets_dir: &Path, buttons: ButtonStyle, locale: Locale) -> Self {
+        ThemedSource {
+            assets: Directory::new(assets_dir),
+            buttons,
+            locale,
+        }
+    }
+}
+
+impl Source for ThemedSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.assets.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let bytes = self.assets.load(path)?;
+        if path.starts_with("ui/") && path.ends_with(".ron") {
+            let prefab = String::from_utf8_lossy(&bytes);
+            let prefab = self.buttons.restyle(&prefab);
+            Ok(self.locale.localize(&prefab).into_bytes())
+        } else {
+            Ok(bytes)
+        }
+    }
+}
+
+/// Replace the placeholders of a UI prefab, string literals starting with `@`,
+/// with the RON `fill` returns for the rest of the string.
+/// Placeholders `fill` doesn't know, comments and other strings are kept as they are.
+pub fn fill_placeholders<F>(prefab: &str, fill: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut filled = String::with_capacity(prefab.len());
+    let mut rest = prefab;
+    while let Some(start) = rest.find(|c| c == '"' || c == '/') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |end| end + 2)
+        } else if rest.starts_with('"') {
+            let end = string_end(rest);
+            let value = rest[..end]
+                .strip_prefix("\"@")
+                .and_then(|key| key.strip_suffix('"'))
+                .and_then(&fill);
+            if let Some(value) = value {
+                filled.push_str(&value);
+                rest = &rest[end..];
+                continue;
+            }
+            end
+        } else {
+            1
+        };
+        filled.push_str(&rest[..end]);
+        rest = &rest[end..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Length of the string literal `source` starts with, including the quotes.
+fn string_end(source: &str) -> usize {
+    let bytes = source.as_bytes();
+    let mut pos = 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            b'"' => return pos + 1,
+            _ => pos += 1,
+        }
+    }
+    source.len()
+}
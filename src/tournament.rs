@@ -0,0 +1,287 @@
+//! Tournaments for local players.
+//!
+//! Every match of a tournament is a regular two player match.
+//! Elimination rounds are generated lazily, once the previous round
+//! has been played, so byes and the double elimination losers pool
+//! fall out naturally.
+use serde::{Deserialize, Serialize};
+
+use std::{io, path::Path};
+
+use crate::{
+    locale::Locale,
+    paths::{load_ron, save_ron},
+    pong::ScoreBoard,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    SingleElimination,
+    /// Players are out after their second loss.
+    /// Players without a loss and players with one loss are paired
+    /// separately, the last undefeated player meets the last player
+    /// with one loss in the grand final.
+    DoubleElimination,
+    RoundRobin,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Match {
+    pub round: usize,
+    pub left: usize,
+    pub right: usize,
+    /// Final score as `(left, right)`, once played.
+    pub score: Option<(i32, i32)>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tournament {
+    pub format: Format,
+    pub players: Vec<String>,
+    pub matches: Vec<Match>,
+    losses: Vec<u32>,
+    byes: Vec<u32>,
+    round: usize,
+}
+
+impl Format {
+    /// The format following this one, used to cycle through the formats in the menu.
+    pub fn next(self) -> Self {
+        match self {
+            Format::SingleElimination => Format::DoubleElimination,
+            Format::DoubleElimination => Format::RoundRobin,
+            Format::RoundRobin => Format::SingleElimination,
+        }
+    }
+
+    /// The name shown in the menu.
+    pub fn display(self, locale: &Locale) -> &str {
+        locale.get(match self {
+            Format::SingleElimination => "tournament.single_elimination",
+            Format::DoubleElimination => "tournament.double_elimination",
+            Format::RoundRobin => "tournament.round_robin",
+        })
+    }
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::SingleElimination
+    }
+}
+
+impl Match {
+    pub fn winner(&self) -> Option<usize> {
+        match self.score {
+            Some((left, right)) if left > right => Some(self.left),
+            Some((left, right)) if right > left => Some(self.right),
+            _ => None,
+        }
+    }
+
+    pub fn loser(&self) -> Option<usize> {
+        match self.winner() {
+            Some(winner) if winner == self.left => Some(self.right),
+            Some(_) => Some(self.left),
+            None => None,
+        }
+    }
+}
+
+impl Tournament {
+    /// Create a new tournament. Players are seeded in the given order.
+    /// Returns `None` if there are less than two players.
+    pub fn new(format: Format, players: Vec<String>) -> Option<Self> {
+        if players.len() < 2 {
+            return None;
+        }
+        let mut tournament = Tournament {
+            format,
+            losses: vec![0; players.len()],
+            byes: vec![0; players.len()],
+            players,
+            matches: vec![],
+            round: 0,
+        };
+        match format {
+            Format::RoundRobin => tournament.schedule_round_robin(),
+            _ => tournament.schedule_elimination_round(),
+        }
+        Some(tournament)
+    }
+
+    /// Load a tournament from a RON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        load_ron(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        save_ron(self, path)
+    }
+
+    /// Index of the next match that has to be played.
+    pub fn next_match(&self) -> Option<usize> {
+        self.matches.iter().position(|m| m.score.is_none())
+    }
+
+    /// Names of the players in the next match as `(left, right)`.
+    pub fn next_players(&self) -> Option<(&str, &str)> {
+        self.next_match().map(|index| {
+            let next = &self.matches[index];
+            (
+                self.players[next.left].as_str(),
+                self.players[next.right].as_str(),
+            )
+        })
+    }
+
+    /// Record the result of the next match.
+    /// Draws cannot advance a bracket and are ignored.
+    pub fn record(&mut self, scores: &ScoreBoard) {
+        if scores.score_left == scores.score_right {
+            return;
+        }
+        let index = match self.next_match() {
+            Some(index) => index,
+            None => return,
+        };
+        self.matches[index].score = Some((scores.score_left, scores.score_right));
+        if let Some(loser) = self.matches[index].loser() {
+            self.losses[loser] += 1;
+        }
+        if self.next_match().is_none() && self.format != Format::RoundRobin {
+            self.schedule_elimination_round();
+        }
+    }
+
+    /// The winner of the tournament, once it's decided.
+    pub fn champion(&self) -> Option<usize> {
+        if self.next_match().is_some() {
+            return None;
+        }
+        match self.format {
+            Format::RoundRobin => self.standings().first().map(|&(player, _, _)| player),
+            _ => {
+                let mut alive = self.alive();
+                if alive.len() == 1 {
+                    alive.pop()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// A textual overview of the tournament for the bracket screen.
+    pub fn display(&self, locale: &Locale) -> String {
+        let mut lines = vec![self.format.display(locale).to_string()];
+        let mut round = None;
+        for m in &self.matches {
+            if round != Some(m.round) {
+                round = Some(m.round);
+                lines.push(locale.format("tournament.round", &[&(m.round + 1).to_string()]));
+            }
+            let left = &self.players[m.left];
+            let right = &self.players[m.right];
+            lines.push(match m.score {
+                Some((l, r)) => format!("  {} {} : {} {}", left, l, r, right),
+                None => format!("  {}", locale.format("tournament.versus", &[left, right])),
+            });
+        }
+        if let Some(champion) = self.champion() {
+            lines.push(locale.format("tournament.champion", &[&self.players[champion]]));
+        }
+        lines.join("\n")
+    }
+
+    /// Players ordered by wins, then by point difference, as `(player, wins, difference)`.
+    pub fn standings(&self) -> Vec<(usize, u32, i32)> {
+        let mut standings: Vec<_> = (0..self.players.len()).map(|p| (p, 0, 0)).collect();
+        for m in &self.matches {
+            if let (Some((left, right)), Some(winner)) = (m.score, m.winner()) {
+                standings[winner].1 += 1;
+                standings[m.left].2 += left - right;
+                standings[m.right].2 += right - left;
+            }
+        }
+        standings.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+        standings
+    }
+
+    /// Players that are not eliminated yet.
+    fn alive(&self) -> Vec<usize> {
+        let allowed = match self.format {
+            Format::DoubleElimination => 2,
+            _ => 1,
+        };
+        (0..self.players.len())
+            .filter(|&p| self.losses[p] < allowed)
+            .collect()
+    }
+
+    fn schedule_elimination_round(&mut self) {
+        let alive = self.alive();
+        let (undefeated, once_defeated): (Vec<_>, Vec<_>) =
+            alive.into_iter().partition(|&p| self.losses[p] == 0);
+        let round = self.round;
+        if undefeated.len() == 1 && once_defeated.len() == 1 {
+            // Grand final
+            self.push_match(round, undefeated[0], once_defeated[0]);
+        } else {
+            self.pair(round, undefeated);
+            self.pair(round, once_defeated);
+        }
+        self.round += 1;
+    }
+
+    /// Pair the given players by seed, the player with the fewest byes sits out
+    /// if the number of players is odd.
+    fn pair(&mut self, round: usize, mut players: Vec<usize>) {
+        if players.len() < 2 {
+            return;
+        }
+        if players.len() % 2 == 1 {
+            let byes = &self.byes;
+            let resting = (0..players.len())
+                .rev()
+                .min_by_key(|&i| byes[players[i]])
+                .expect("At least three players");
+            let resting = players.remove(resting);
+            self.byes[resting] += 1;
+        }
+        let half = players.len() / 2;
+        for i in 0..half {
+            // Best seed against worst seed
+            self.push_match(round, players[i], players[players.len() - 1 - i]);
+        }
+    }
+
+    /// Schedule all rounds using the circle method.
+    fn schedule_round_robin(&mut self) {
+        let mut circle: Vec<Option<usize>> = (0..self.players.len()).map(Some).collect();
+        if circle.len() % 2 == 1 {
+            circle.push(None);
+        }
+        let n = circle.len();
+        for round in 0..n - 1 {
+            for i in 0..n / 2 {
+                if let (Some(left), Some(right)) = (circle[i], circle[n - 1 - i]) {
+                    self.push_match(round, left, right);
+                }
+            }
+            // Keep the first player fixed and rotate everyone else.
+            let last = circle.pop().expect("At least two players");
+            circle.insert(1, last);
+        }
+        self.round = n - 1;
+    }
+
+    fn push_match(&mut self, round: usize, left: usize, right: usize) {
+        self.matches.push(Match {
+            round,
+            left,
+            right,
+            score: None,
+        });
+    }
+}
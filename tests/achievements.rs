@@ -0,0 +1,78 @@
+//! Achievement goals and the shipped achievements.
+use amethyst_pong::{
+    achievements::{Achievement, AchievementProgress, Achievements, Feat},
+    audio::ScoreSound,
+    config::Layers,
+};
+
+use std::{collections::HashSet, path::PathBuf};
+
+fn shipped() -> Achievements {
+    let layers = Layers {
+        shipped: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("config"),
+        user: None,
+        overrides: None,
+    };
+    layers
+        .load("achievements", &Achievements::default(), "achievements")
+        .unwrap()
+}
+
+#[test]
+fn shipped_achievements_have_unique_ids() {
+    let achievements = shipped();
+    let ids = achievements
+        .achievements
+        .iter()
+        .map(|achievement| achievement.id.as_str())
+        .collect::<HashSet<_>>();
+    assert!(!ids.is_empty());
+    assert_eq!(ids.len(), achievements.achievements.len());
+}
+
+#[test]
+fn feats_unlock_achievements_once() {
+    let achievements = shipped();
+    let mut progress = AchievementProgress::default();
+    let names = |unlocked: Vec<&Achievement>| {
+        unlocked
+            .iter()
+            .map(|achievement| achievement.id.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(progress
+        .unlock(&achievements, Feat::PaddleHit { hits: 9 })
+        .is_empty());
+    assert_eq!(
+        names(progress.unlock(&achievements, Feat::PaddleHit { hits: 30 })),
+        ["rally_10", "rally_30"]
+    );
+    assert!(progress
+        .unlock(&achievements, Feat::PaddleHit { hits: 31 })
+        .is_empty());
+
+    let spinning = Feat::Score {
+        spin: -55.0,
+        wall_bounces: 2,
+    };
+    assert_eq!(
+        names(progress.unlock(&achievements, spinning)),
+        ["max_spin_score", "bank_shot"]
+    );
+    assert!(progress
+        .unlock(&achievements, Feat::Win { conceded: 1 })
+        .is_empty());
+    assert_eq!(
+        names(progress.unlock(&achievements, Feat::Win { conceded: 0 })),
+        ["clean_sheet"]
+    );
+    assert!(progress
+        .unlock(&achievements, Feat::Heard(ScoreSound::Score))
+        .is_empty());
+    assert_eq!(
+        names(progress.unlock(&achievements, Feat::Heard(ScoreSound::Wilhelm))),
+        ["wilhelm"]
+    );
+    assert_eq!(progress.unlocked.len(), achievements.achievements.len());
+}
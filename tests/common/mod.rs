@@ -0,0 +1,188 @@
+//! Helpers to set up situations in a headless match and to look at the result.
+#![allow(dead_code)]
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    audio::{AudioData, Source},
+    core::{math::Vector3, Transform},
+    ecs::{Join, World, WorldExt},
+    input::{
+        Bindings, ControllerButton, ControllerEvent, InputEvent, InputHandler, StringBindings,
+    },
+    shrev::EventChannel,
+};
+use amethyst_pong::{
+    audio::Sounds,
+    config,
+    headless::Simulation,
+    pong::{Ball, Paddle, ScoreBoard, Side},
+    replay::Frame,
+    rules::MatchRules,
+};
+
+use std::{path::Path, sync::Arc};
+
+/// Length of a single frame in seconds.
+pub const FRAME: f32 = 1.0 / 60.0;
+
+/// An endless match between two paddles without AI.
+/// The paddles only move, if the test steers them.
+pub fn match_without_ai() -> Simulation<'static, 'static> {
+    match_with_rules(&MatchRules::default())
+}
+
+/// A match between two players with the shipped bindings and silent sounds.
+pub fn match_with_rules(rules: &MatchRules) -> Simulation<'static, 'static> {
+    let mut sim = Simulation::new(rules, None, None, 0);
+    let bindings = config::load_file::<Bindings<StringBindings>>(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("config/bindings.ron")
+            .as_path(),
+        "the shipped key bindings",
+    )
+    .expect("Shipped bindings load");
+    sim.world
+        .write_resource::<InputHandler<StringBindings>>()
+        .bindings = bindings;
+    sim.world
+        .entry::<EventChannel<InputEvent<StringBindings>>>()
+        .or_insert_with(EventChannel::new);
+    insert_silent_sounds(&mut sim.world);
+    sim
+}
+
+/// Sounds without any audio in them, so scoring goes through the sound code of a real match.
+fn insert_silent_sounds(world: &mut World) {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("Thread pool builds");
+    let loader = Loader::new(env!("CARGO_MANIFEST_DIR"), Arc::new(pool));
+    let storage = AssetStorage::<Source>::default();
+    let silence = || loader.load_from_data(AudioData(Vec::new()), (), &storage);
+    let sounds = Sounds {
+        score_sfx: silence(),
+        wilhelm_sfx: silence(),
+        roblox_death_sfx: silence(),
+        bounce_wall_sfx: silence(),
+        bounce_paddle_sfx: silence(),
+    };
+    world.insert(sounds);
+    world.insert(storage);
+    world.insert(loader);
+}
+
+/// Hold the paddle axis of the given side at `value`, `0.0` lets go of it.
+/// The paddles read it like the input of a replayed frame.
+pub fn steer(sim: &mut Simulation<'_, '_>, side: Side, value: f32) {
+    let mut frame = sim.world.entry::<Frame>().or_insert_with(|| Frame {
+        delta: FRAME,
+        left_paddle: Some(0.0),
+        right_paddle: Some(0.0),
+    });
+    match side {
+        Side::Left => frame.left_paddle = Some(value),
+        Side::Right => frame.right_paddle = Some(value),
+    }
+}
+
+/// Press a button of the first gamepad, like the gamepad system reports it to the game.
+pub fn press_button(sim: &mut Simulation<'_, '_>, button: ControllerButton) {
+    let mut events = sim
+        .world
+        .write_resource::<EventChannel<InputEvent<StringBindings>>>();
+    sim.world
+        .write_resource::<InputHandler<StringBindings>>()
+        .send_controller_event(
+            &ControllerEvent::ControllerButtonPressed { which: 0, button },
+            &mut events,
+        );
+}
+
+/// Put the ball in play at the given position with the given velocity and no spin.
+pub fn put_ball(sim: &mut Simulation<'_, '_>, x: f32, y: f32, velocity: Vector3<f32>) {
+    let mut balls = sim.world.write_storage::<Ball>();
+    let mut transforms = sim.world.write_storage::<Transform>();
+    for (ball, transform) in (&mut balls, &mut transforms).join() {
+        transform.set_translation_xyz(x, y, 0.0);
+        ball.velocity = velocity;
+        ball.rot_velocity = 0.0;
+        ball.hidden = None;
+    }
+}
+
+/// Give the ball the given spin.
+pub fn put_spin(sim: &mut Simulation<'_, '_>, spin: f32) {
+    let mut balls = sim.world.write_storage::<Ball>();
+    for ball in (&mut balls).join() {
+        ball.rot_velocity = spin;
+    }
+}
+
+/// Position, velocity, spin and hidden timer of the ball.
+pub fn ball(sim: &Simulation<'_, '_>) -> (Vector3<f32>, Vector3<f32>, f32, Option<f32>) {
+    let balls = sim.world.read_storage::<Ball>();
+    let transforms = sim.world.read_storage::<Transform>();
+    let (ball, transform) = (&balls, &transforms).join().next().expect("A ball");
+    (
+        *transform.translation(),
+        ball.velocity,
+        ball.rot_velocity,
+        ball.hidden,
+    )
+}
+
+/// Move the paddle on the given side to `y` and stop it there.
+pub fn put_paddle(sim: &mut Simulation<'_, '_>, side: Side, y: f32) {
+    let mut paddles = sim.world.write_storage::<Paddle>();
+    let mut transforms = sim.world.write_storage::<Transform>();
+    for (paddle, transform) in (&mut paddles, &mut transforms).join() {
+        if paddle.side == side {
+            transform.set_translation_y(y);
+            paddle.velocity = 0.0;
+        }
+    }
+}
+
+/// Vertical position of the paddle on the given side.
+pub fn paddle_y(sim: &Simulation<'_, '_>, side: Side) -> f32 {
+    let paddles = sim.world.read_storage::<Paddle>();
+    let transforms = sim.world.read_storage::<Transform>();
+    (&paddles, &transforms)
+        .join()
+        .find(|(paddle, _)| paddle.side == side)
+        .map(|(_, transform)| transform.translation().y)
+        .expect("A paddle on every side")
+}
+
+pub fn paddle(sim: &Simulation<'_, '_>, side: Side) -> Paddle {
+    let paddles = sim.world.read_storage::<Paddle>();
+    let paddle = (&paddles)
+        .join()
+        .find(|paddle| paddle.side == side)
+        .expect("A paddle on every side");
+    Paddle {
+        side: paddle.side,
+        width: paddle.width,
+        height: paddle.height,
+        velocity: paddle.velocity,
+    }
+}
+
+/// The score as `(left, right)`.
+pub fn scores(sim: &Simulation<'_, '_>) -> (i32, i32) {
+    let scores = sim.world.read_resource::<ScoreBoard>();
+    (scores.score_left, scores.score_right)
+}
+
+/// Step `frames` frames. Before every frame `script` gets to play the role
+/// of the players, e.g. by pressing keys.
+pub fn run<F>(sim: &mut Simulation<'_, '_>, frames: usize, mut script: F)
+where
+    F: FnMut(&mut Simulation<'_, '_>, usize),
+{
+    for frame in 0..frames {
+        script(sim, frame);
+        sim.step(FRAME);
+    }
+}
@@ -0,0 +1,174 @@
+//! Resolving config files from the layered search path.
+use amethyst_pong::{
+    config::{Layers, Settings},
+    error::StartupError,
+    rules::{MatchRules, TieBreak},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::{env, fs, path::PathBuf, process};
+
+const EXPECTED: &str = "match rules";
+const SHIPPED: &str = "(
+    duration: Some(180.0),
+    tie_break: Overtime(
+        duration: 60.0,
+        speed_factor: 1.3,
+    ),
+)";
+
+/// A fresh directory with the shipped and user config directories in it.
+fn dirs(test: &str) -> (PathBuf, PathBuf) {
+    let root = env::temp_dir().join(format!("pong-config-{}-{}", process::id(), test));
+    let _ = fs::remove_dir_all(&root);
+    let (shipped, user) = (root.join("shipped"), root.join("user"));
+    fs::create_dir_all(&shipped).unwrap();
+    fs::create_dir_all(&user).unwrap();
+    fs::write(shipped.join("rules.ron"), SHIPPED).unwrap();
+    (shipped, user)
+}
+
+fn load(
+    shipped: PathBuf,
+    user: PathBuf,
+    overrides: Option<PathBuf>,
+) -> Result<MatchRules, StartupError> {
+    let layers = Layers {
+        shipped,
+        user: Some(user),
+        overrides,
+    };
+    layers.load("rules", &MatchRules::default(), EXPECTED)
+}
+
+#[test]
+fn missing_files_fall_back_to_the_defaults() {
+    let (shipped, user) = dirs("defaults");
+    fs::remove_file(shipped.join("rules.ron")).unwrap();
+    let rules = load(shipped, user, None).unwrap();
+    assert_eq!(rules.duration, MatchRules::default().duration);
+}
+
+#[test]
+fn user_file_overrides_single_keys() {
+    let (shipped, user) = dirs("user");
+    fs::write(
+        user.join("rules.ron"),
+        "(tie_break: Overtime(duration: 10.0))",
+    )
+    .unwrap();
+    let rules = load(shipped, user, None).unwrap();
+
+    assert_eq!(rules.duration, Some(180.0));
+    match rules.tie_break {
+        TieBreak::Overtime {
+            duration,
+            speed_factor,
+        } => {
+            assert_eq!(duration, 10.0);
+            assert_eq!(speed_factor, 1.3);
+        }
+        other => panic!("expected overtime, got {:?}", other),
+    }
+}
+
+#[test]
+fn overrides_file_wins_over_user_file() {
+    let (shipped, user) = dirs("overrides");
+    fs::write(
+        user.join("rules.ron"),
+        "(duration: Some(90.0), tie_break: SuddenDeath)",
+    )
+    .unwrap();
+    let overrides = user.join("overrides.ron");
+    fs::write(
+        &overrides,
+        "(rules: (duration: None), display: (title: \"Test\"))",
+    )
+    .unwrap();
+    let rules = load(shipped, user, Some(overrides)).unwrap();
+
+    assert_eq!(rules.duration, None);
+    match rules.tie_break {
+        TieBreak::SuddenDeath => {}
+        other => panic!("expected sudden death, got {:?}", other),
+    }
+}
+
+#[test]
+fn errors_point_into_the_file_with_the_bad_value() {
+    let (shipped, user) = dirs("errors");
+    fs::write(user.join("rules.ron"), "(\n    tie_break: Coin,\n)").unwrap();
+    match load(shipped, user.clone(), None) {
+        Err(StartupError::Parse {
+            path, line, col, ..
+        }) => {
+            assert_eq!(path, user.join("rules.ron"));
+            assert_eq!((line, col), (2, 16));
+        }
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    }
+}
+
+/// The merged config file `name` must be what ron itself reads from the shipped file.
+fn assert_read_as_ron<T: Serialize + DeserializeOwned>(name: &str, merged: &T) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("config")
+        .join(format!("{}.ron", name));
+    let source = fs::read_to_string(&path).unwrap();
+    let parsed: T = ron::de::from_str(&source)
+        .unwrap_or_else(|e| panic!("{} is no valid RON: {}", path.display(), e));
+    assert_eq!(
+        ron::ser::to_string(merged).unwrap(),
+        ron::ser::to_string(&parsed).unwrap(),
+        "{} merged differently than ron reads it",
+        name
+    );
+}
+
+#[test]
+fn shipped_files_merge_like_ron_reads_them() {
+    let shipped = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("config");
+    // Once over the defaults, once more over themselves as the user's files.
+    for user in &[None, Some(shipped.clone())] {
+        let layers = Layers {
+            shipped: shipped.clone(),
+            user: user.clone(),
+            overrides: None,
+        };
+        let settings = Settings::load(&layers).unwrap();
+        assert_read_as_ron("display", &settings.display);
+        assert_read_as_ron("appearance", &settings.appearance);
+        assert_read_as_ron("bindings", &settings.bindings);
+        assert_read_as_ron("rules", &settings.rules);
+        assert_read_as_ron("practice", &settings.practice);
+        assert_read_as_ron("campaign", &settings.campaign);
+        assert_read_as_ron("effects", &settings.effects);
+        assert_read_as_ron("hud", &settings.hud);
+        assert_read_as_ron("accessibility", &settings.accessibility);
+        assert_read_as_ron("achievements", &settings.achievements);
+        assert_read_as_ron("attract", &settings.attract);
+        assert_read_as_ron("menu", &settings.menu);
+        assert_read_as_ron("pause", &settings.pause);
+    }
+}
+
+#[test]
+fn dumped_settings_read_back_as_overrides() {
+    let shipped = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("config");
+    let (_, user) = dirs("dump");
+    let layers = Layers {
+        shipped: shipped.clone(),
+        user: None,
+        overrides: None,
+    };
+    let dump = Settings::load(&layers).unwrap().dump();
+    let overrides = user.join("overrides.ron");
+    fs::write(&overrides, &dump).unwrap();
+    let layers = Layers {
+        shipped,
+        user: None,
+        overrides: Some(overrides),
+    };
+    assert_eq!(Settings::load(&layers).unwrap().dump(), dump);
+}
@@ -0,0 +1,278 @@
+//! Drives headless matches frame by frame and checks the game logic.
+mod common;
+
+use amethyst::{
+    core::math::Vector3,
+    input::{ControllerButton, InputEvent, StringBindings},
+    prelude::{StateEvent, World, WorldExt},
+    shrev::EventChannel,
+};
+use amethyst_pong::{
+    accessibility::AccessibilityConfig,
+    effects::EffectsConfig,
+    headless::Simulation,
+    pause::PauseConfig,
+    pong::{
+        pause_requested, predict_path, Ai, Side, ARENA_HEIGHT, ARENA_WIDTH, BALL_RADIUS_COLLISION,
+        BOTTOM_OF_SCREEN, INITIAL_BALL_SPEED, MAX_ROTATION_ON_COLLISION, ROT_FACTOR,
+    },
+    replay::{Frame, PlaySettings},
+    rules::{MatchPhase, MatchRules, TieBreak},
+};
+
+use common::*;
+
+const EPSILON: f32 = 1e-3;
+
+/// Angle of the velocity above the horizontal in radians.
+fn angle(velocity: Vector3<f32>) -> f32 {
+    velocity.y.atan2(velocity.x)
+}
+
+#[test]
+fn ball_hitting_the_top_of_the_left_paddle_returns_at_40_degrees() {
+    let mut sim = match_without_ai();
+    let paddle = paddle(&sim, Side::Left);
+    put_paddle(&mut sim, Side::Left, 50.0);
+    put_ball(
+        &mut sim,
+        4.5,
+        50.0 + paddle.height * 0.5,
+        Vector3::new(-INITIAL_BALL_SPEED, 0.0, 0.0),
+    );
+
+    sim.step(FRAME);
+
+    let (_, velocity, _, _) = ball(&sim);
+    assert!((angle(velocity) - MAX_ROTATION_ON_COLLISION).abs() < EPSILON);
+    assert!((angle(velocity).to_degrees() - 40.0).abs() < EPSILON);
+    assert!((velocity.norm() - INITIAL_BALL_SPEED * 1.1).abs() < EPSILON);
+}
+
+#[test]
+fn ball_hitting_the_middle_of_the_right_paddle_returns_straight() {
+    let mut sim = match_without_ai();
+    put_paddle(&mut sim, Side::Right, 30.0);
+    put_ball(
+        &mut sim,
+        ARENA_WIDTH - 4.5,
+        30.0,
+        Vector3::new(INITIAL_BALL_SPEED, 0.0, 0.0),
+    );
+
+    sim.step(FRAME);
+
+    let (_, velocity, _, _) = ball(&sim);
+    assert!(velocity.x < 0.0);
+    assert!(velocity.y.abs() < EPSILON);
+}
+
+#[test]
+fn moving_paddle_puts_spin_on_the_ball() {
+    let mut sim = match_without_ai();
+    put_paddle(&mut sim, Side::Left, 30.0);
+    // Get the left paddle moving upwards, while the ball flies to the right.
+    put_ball(&mut sim, 50.0, 50.0, Vector3::new(20.0, 0.0, 0.0));
+    steer(&mut sim, Side::Left, 1.0);
+    run(&mut sim, 5, |_, _| {});
+    let y = paddle_y(&sim, Side::Left);
+    put_ball(
+        &mut sim,
+        4.5,
+        y,
+        Vector3::new(-INITIAL_BALL_SPEED, 0.0, 0.0),
+    );
+    sim.step(FRAME);
+
+    let paddle_velocity = paddle(&sim, Side::Left).velocity;
+    let (_, velocity, spin, _) = ball(&sim);
+    assert!(paddle_velocity > 0.0);
+    assert!(velocity.x > 0.0);
+    assert!((spin - ROT_FACTOR * paddle_velocity / BALL_RADIUS_COLLISION).abs() < EPSILON);
+}
+
+#[test]
+fn spinning_ball_flies_the_predicted_curve() {
+    let mut sim = match_without_ai();
+    put_ball(&mut sim, 30.0, 50.0, Vector3::new(40.0, 0.0, 0.0));
+    put_spin(&mut sim, 30.0);
+    let (position, velocity, spin, _) = ball(&sim);
+    let path = predict_path(position, velocity, spin, FRAME, 30);
+
+    run(&mut sim, 30, |_, _| {});
+
+    let (position, velocity, _, _) = ball(&sim);
+    assert_eq!(path.len(), 30);
+    assert!((path[29] - position).norm() < EPSILON);
+    // Counterclockwise spin bends a ball flying to the right upwards.
+    assert!(velocity.y > 0.0);
+}
+
+#[test]
+fn ball_passing_the_left_edge_scores_for_the_right_player() {
+    let mut sim = match_without_ai();
+    put_ball(
+        &mut sim,
+        2.0,
+        10.0,
+        Vector3::new(-INITIAL_BALL_SPEED, 0.0, 0.0),
+    );
+
+    sim.step(FRAME);
+
+    assert_eq!(scores(&sim), (0, 1));
+    let (position, velocity, _, hidden) = ball(&sim);
+    assert!((position.x - ARENA_WIDTH / 2.0).abs() < EPSILON);
+    assert!((position.y - ARENA_HEIGHT / 2.0).abs() < EPSILON);
+    assert!(hidden.is_some());
+    // The next serve goes to the player that scored.
+    assert!(velocity.x > 0.0);
+}
+
+#[test]
+fn ball_passing_the_right_edge_scores_for_the_left_player() {
+    let mut sim = match_without_ai();
+    put_ball(
+        &mut sim,
+        ARENA_WIDTH - 2.0,
+        10.0,
+        Vector3::new(INITIAL_BALL_SPEED, 0.0, 0.0),
+    );
+
+    sim.step(FRAME);
+
+    assert_eq!(scores(&sim), (1, 0));
+}
+
+#[test]
+fn ball_bounces_off_the_top_wall() {
+    let mut sim = match_without_ai();
+    put_ball(
+        &mut sim,
+        50.0,
+        ARENA_HEIGHT - 1.5,
+        Vector3::new(0.0, INITIAL_BALL_SPEED, 0.0),
+    );
+
+    sim.step(FRAME);
+
+    let (_, velocity, _, _) = ball(&sim);
+    assert!((velocity.y + INITIAL_BALL_SPEED).abs() < EPSILON);
+    assert!(velocity.x.abs() < EPSILON);
+}
+
+#[test]
+fn paddle_stops_at_the_top_of_the_arena() {
+    let mut sim = match_without_ai();
+    // Hold the left paddle's axis upwards.
+    steer(&mut sim, Side::Left, 1.0);
+    run(&mut sim, 120, |_, _| {});
+
+    assert!((paddle_y(&sim, Side::Left) - BOTTOM_OF_SCREEN).abs() < EPSILON);
+    assert!((paddle_y(&sim, Side::Right) - ARENA_HEIGHT / 2.0).abs() < EPSILON);
+}
+
+#[test]
+fn paddles_move_until_they_are_let_go() {
+    let mut sim = match_without_ai();
+    steer(&mut sim, Side::Left, -1.0);
+    steer(&mut sim, Side::Right, 1.0);
+    run(&mut sim, 10, |_, _| {});
+    let (left, right) = (paddle_y(&sim, Side::Left), paddle_y(&sim, Side::Right));
+    assert!(left < ARENA_HEIGHT / 2.0);
+    assert!(right > ARENA_HEIGHT / 2.0);
+
+    steer(&mut sim, Side::Left, 0.0);
+    steer(&mut sim, Side::Right, 0.0);
+    run(&mut sim, 120, |_, _| {});
+    let stopped = (paddle_y(&sim, Side::Left), paddle_y(&sim, Side::Right));
+    sim.step(FRAME);
+    assert!((paddle_y(&sim, Side::Left) - stopped.0).abs() < EPSILON);
+    assert!((paddle_y(&sim, Side::Right) - stopped.1).abs() < EPSILON);
+}
+
+#[test]
+fn pause_button_asks_for_a_pause() {
+    let mut sim = match_without_ai();
+    let mut reader = sim
+        .world
+        .write_resource::<EventChannel<InputEvent<StringBindings>>>()
+        .register_reader();
+    press_button(&mut sim, ControllerButton::Start);
+
+    let events = sim
+        .world
+        .read_resource::<EventChannel<InputEvent<StringBindings>>>()
+        .read(&mut reader)
+        .cloned()
+        .collect::<Vec<_>>();
+    let config = PauseConfig::default();
+    assert!(events
+        .into_iter()
+        .any(|event| pause_requested(&StateEvent::Input(event), &config)));
+}
+
+#[test]
+fn replayed_input_moves_the_paddles() {
+    let mut sim = match_without_ai();
+    sim.world.insert(Frame {
+        delta: FRAME,
+        left_paddle: Some(1.0),
+        right_paddle: Some(-1.0),
+    });
+    run(&mut sim, 30, |_, _| {});
+
+    assert!(paddle_y(&sim, Side::Left) > ARENA_HEIGHT / 2.0);
+    assert!(paddle_y(&sim, Side::Right) < ARENA_HEIGHT / 2.0);
+}
+
+#[test]
+fn replay_settings_replace_the_configured_ones_until_restored() {
+    let mut world = World::new();
+    let recorded = PlaySettings {
+        ball_scale: 2.0,
+        hit_stop: false,
+    };
+    let configured = recorded.apply(&mut world);
+
+    assert_eq!(world.read_resource::<AccessibilityConfig>().ball_scale, 2.0);
+    assert!(!world.read_resource::<EffectsConfig>().hit_stop);
+    configured.apply(&mut world);
+    assert_eq!(PlaySettings::current(&mut world), configured);
+    assert_eq!(configured.ball_scale, 1.0);
+}
+
+#[test]
+fn sudden_death_ends_the_match_with_the_next_point() {
+    let mut sim = match_with_rules(&MatchRules {
+        duration: Some(1.0),
+        tie_break: TieBreak::SuddenDeath,
+    });
+    // Keep the ball bouncing between the walls until the time is up.
+    put_ball(&mut sim, 50.0, 50.0, Vector3::new(0.0, 20.0, 0.0));
+    run(&mut sim, 70, |_, _| {});
+    assert_eq!(sim.phase(), MatchPhase::SuddenDeath);
+
+    put_ball(
+        &mut sim,
+        2.0,
+        10.0,
+        Vector3::new(-INITIAL_BALL_SPEED, 0.0, 0.0),
+    );
+    sim.step(FRAME);
+
+    assert_eq!(sim.phase(), MatchPhase::Finished(Side::Right));
+}
+
+#[test]
+fn same_seed_plays_the_same_match() {
+    let play = || {
+        let ai = Ai::default().params;
+        let mut sim = Simulation::new(&MatchRules::default(), Some(ai.clone()), Some(ai), 7);
+        run(&mut sim, 1200, |_, _| {});
+        let (position, velocity, _, _) = ball(&sim);
+        (position, velocity, scores(&sim))
+    };
+
+    assert_eq!(play(), play());
+}
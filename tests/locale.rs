@@ -0,0 +1,107 @@
+//! The shipped string tables and the translated UI prefabs.
+use amethyst_pong::{
+    locale::{Locale, FALLBACK_LANGUAGE},
+    theme::Theme,
+};
+
+use std::{env, fs, path::PathBuf, process};
+
+const LANGUAGES: [&str; 3] = ["en", "de", "ru"];
+
+fn assets_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets")
+}
+
+#[test]
+fn shipped_languages_are_complete() {
+    let fallback = Locale::load(&assets_dir(), FALLBACK_LANGUAGE).unwrap();
+    for language in &LANGUAGES {
+        let locale = Locale::load(&assets_dir(), language).unwrap();
+        if let Some(font) = &locale.font {
+            assert!(assets_dir().join(font).is_file(), "{} is missing", font);
+        }
+        // Loading fills in missing strings, so compare with the file itself
+        let path = assets_dir()
+            .join("locale")
+            .join(format!("{}.ron", language));
+        let own: Locale = ron::de::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        for key in fallback.strings.keys() {
+            assert!(own.strings.contains_key(key), "{} lacks {}", language, key);
+        }
+    }
+}
+
+#[test]
+fn prefabs_are_translated() {
+    let locale = Locale::load(&assets_dir(), "ru").unwrap();
+    for entry in fs::read_dir(assets_dir().join("ui")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("ron") {
+            continue;
+        }
+        let prefab = fs::read_to_string(&path).unwrap();
+        let localized = locale.localize(&Theme::default().buttons.restyle(&prefab));
+
+        assert!(!localized.contains("\"@"), "{:?} has unknown keys", path);
+        assert!(!localized.contains("square.ttf"));
+        assert_eq!(localized.lines().count(), prefab.lines().count());
+    }
+    let menu = locale.localize(&fs::read_to_string(assets_dir().join("ui/main_menu.ron")).unwrap());
+    assert!(menu.contains("text: \"Выход\","));
+}
+
+#[test]
+fn placeholders_are_filled_in_any_layout() {
+    let locale = Locale::load(&assets_dir(), "ru").unwrap();
+    let prefab = "Label(text: (text: \"@main_menu.quit\", font: File(\"@font\", (\"TTF\", ())))),
+        // \"@main_menu.quit\" in a comment
+        Label(text: (text: \"@ not a key\"))";
+
+    assert_eq!(
+        locale.localize(prefab),
+        "Label(text: (text: \"Выход\", font: File(\"font/DejaVuSans.ttf\", (\"TTF\", ())))),
+        // \"@main_menu.quit\" in a comment
+        Label(text: (text: \"@ not a key\"))"
+    );
+}
+
+#[test]
+fn missing_strings_fall_back() {
+    // A language with a single string next to the shipped fallback
+    let assets = env::temp_dir().join(format!("pong-locale-{}", process::id()));
+    let _ = fs::remove_dir_all(&assets);
+    fs::create_dir_all(assets.join("locale")).unwrap();
+    fs::copy(
+        assets_dir().join("locale/en.ron"),
+        assets.join("locale/en.ron"),
+    )
+    .unwrap();
+    fs::write(
+        assets.join("locale/xx.ron"),
+        "(strings: {\"main_menu.quit\": \"Exit\"})",
+    )
+    .unwrap();
+
+    let locale = Locale::load(&assets, "xx").unwrap();
+    assert_eq!(locale.get("main_menu.quit"), "Exit");
+    assert_eq!(locale.get("main_menu.practice"), "Practice");
+    assert_eq!(locale.format("game_over.wins", &["P1"]), "P1 wins!");
+    assert_eq!(
+        locale.format("tournament.versus", &["Alice", "Bob"]),
+        "Alice vs Bob"
+    );
+    assert_eq!(locale.get("no.such.key"), "no.such.key");
+}
+
+#[test]
+fn hud_is_drawn_in_the_language_font() {
+    let mut theme = Theme::load(&assets_dir(), "neon").unwrap();
+    Locale::load(&assets_dir(), "de")
+        .unwrap()
+        .adjust(&mut theme);
+    assert_eq!(theme.score_font, "font/square.ttf");
+    Locale::load(&assets_dir(), "ru")
+        .unwrap()
+        .adjust(&mut theme);
+    assert_eq!(theme.score_font, "font/DejaVuSans.ttf");
+}
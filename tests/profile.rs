@@ -0,0 +1,92 @@
+//! Player profiles, their statistics and ratings.
+use amethyst_pong::{
+    locale::Locale,
+    pong::{Difficulty, ScoreBoard, Side},
+    profile::{Profiles, AVATARS},
+    rating::{leaderboard, rating_change, INITIAL_RATING},
+};
+
+use std::{env, path::PathBuf, process};
+
+#[test]
+fn profiles_survive_a_round_trip() {
+    let path = env::temp_dir().join(format!("pong-profiles-{}.ron", process::id()));
+    let mut profiles = Profiles::default();
+    let alice = profiles.get_or_create("Alice");
+    alice.next_avatar();
+    alice.next_paddle_color();
+    alice.next_paddle_color();
+    profiles.get_or_create("Bob");
+    profiles.save(&path).unwrap();
+
+    let loaded = Profiles::load(&path);
+    let alice = loaded.get("Alice").unwrap();
+    assert_eq!(alice.avatar, AVATARS[1]);
+    assert!(alice.paddle_color.is_some());
+    assert_eq!(loaded.next_name("Alice"), Some("Bob"));
+    assert_eq!(loaded.next_name("Bob"), Some("Alice"));
+    assert_eq!(loaded.next_name("Carol"), Some("Alice"));
+}
+
+#[test]
+fn stats_count_from_the_side_played_on() {
+    let mut profiles = Profiles::default();
+    let scores = ScoreBoard {
+        score_left: 3,
+        score_right: 5,
+    };
+    profiles
+        .get_or_create("Alice")
+        .stats
+        .record(Side::Right, &scores, Side::Right);
+    profiles
+        .get_or_create("Alice")
+        .stats
+        .record(Side::Left, &scores, Side::Right);
+
+    let stats = &profiles.get("Alice").unwrap().stats;
+    assert_eq!((stats.matches, stats.wins, stats.losses), (2, 1, 1));
+    assert_eq!((stats.points_won, stats.points_lost), (8, 8));
+}
+
+#[test]
+fn avatars_are_shipped() {
+    let assets = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets");
+    for avatar in AVATARS.iter() {
+        assert!(assets.join(avatar).is_file(), "{} is missing", avatar);
+    }
+}
+
+#[test]
+fn ratings_move_towards_the_result() {
+    // Equal players trade half the K factor, and whatever one wins the other loses
+    let win = rating_change(1200.0, 1200.0, Side::Left, Side::Left);
+    let loss = rating_change(1200.0, 1200.0, Side::Right, Side::Left);
+    assert!((win - 16.0).abs() < 1e-3);
+    assert!((win + loss).abs() < 1e-3);
+
+    // Beating a much weaker opponent is worth little, losing to it costs a lot
+    let easy = Difficulty::Easy.rating();
+    assert!(rating_change(1500.0, easy, Side::Left, Side::Left) < 5.0);
+    assert!(rating_change(1500.0, easy, Side::Left, Side::Right) < -25.0);
+}
+
+#[test]
+fn leaderboard_ranks_profiles_among_the_ai_levels() {
+    let mut profiles = Profiles::default();
+    profiles.get_or_create("Alice").rating = 1300.0;
+    profiles.get_or_create("Bob");
+
+    let assets_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let locale = Locale::load(&assets_dir, "en").unwrap();
+
+    let names = leaderboard(&profiles, &locale)
+        .into_iter()
+        .map(|standing| standing.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["AI (hard)", "Alice", "Bob", "AI (normal)", "AI (easy)"]
+    );
+    assert_eq!(profiles.get("Bob").unwrap().rating, INITIAL_RATING);
+}
@@ -0,0 +1,52 @@
+//! The shipped themes and the restyled UI prefabs.
+use amethyst_pong::{
+    accessibility::{AccessibilityConfig, Palette},
+    pong::Side,
+    theme::Theme,
+};
+
+use std::{fs, path::PathBuf};
+
+fn assets_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets")
+}
+
+#[test]
+fn shipped_themes_load() {
+    for name in &["neon", "classic"] {
+        let theme = Theme::load(&assets_dir(), name).unwrap();
+        for path in &[&theme.texture, &theme.sprite_sheet, &theme.score_font] {
+            assert!(assets_dir().join(path).is_file(), "{} is missing", path);
+        }
+    }
+}
+
+#[test]
+fn buttons_are_restyled() {
+    let theme = Theme::load(&assets_dir(), "classic").unwrap();
+    let prefab = fs::read_to_string(assets_dir().join("ui/main_menu.ron")).unwrap();
+    let restyled = theme.buttons.restyle(&prefab);
+
+    assert!(!restyled.contains("\"@button."));
+    assert!(restyled
+        .contains("normal_image: Texture(File(\"ui/classic_button.png\", (\"IMAGE\", ()))),"));
+    assert!(restyled.contains("hover_text_color: (0.0, 0.0, 0.0, 1.0),"));
+    assert_eq!(restyled.lines().count(), prefab.lines().count());
+}
+
+#[test]
+fn accessibility_overrides_theme_colours() {
+    let mut theme = Theme::load(&assets_dir(), "neon").unwrap();
+    let config = AccessibilityConfig {
+        high_contrast: true,
+        ..Default::default()
+    };
+    config.adjust(&mut theme);
+    assert_eq!(theme.score_color, [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(theme.background, [0.0, 0.0, 0.0, 1.0]);
+
+    assert_eq!(Palette::Theme.side(Side::Left), None);
+    for palette in &[Palette::RedGreen, Palette::BlueYellow] {
+        assert_ne!(palette.side(Side::Left), palette.side(Side::Right));
+    }
+}